@@ -0,0 +1,60 @@
+use std::time::{Duration, Instant};
+
+use crate::game::Cell;
+
+/// what happens when a shot lands
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Effect {
+    /// a miss: rings spread out across the water
+    Ripple,
+    /// a hit: a burst of fire
+    Explosion,
+    /// every cell of a ship that just sank burns, then goes out
+    Sinking,
+}
+
+impl Effect {
+    fn duration(&self) -> Duration {
+        match self {
+            Effect::Ripple => Duration::from_millis(600),
+            Effect::Explosion => Duration::from_millis(450),
+            Effect::Sinking => Duration::from_millis(1200),
+        }
+    }
+}
+
+/// an effect playing out over some cells of one grid
+#[derive(Debug, Clone)]
+pub struct Animation {
+    pub effect: Effect,
+    pub cells: Vec<Cell>,
+    /// `true` for this player's grid
+    pub this_player: bool,
+    start: Instant,
+}
+
+impl Animation {
+    pub fn new(effect: Effect, cells: Vec<Cell>, this_player: bool, start: Instant) -> Self {
+        Animation {
+            effect,
+            cells,
+            this_player,
+            start,
+        }
+    }
+
+    /// when this animation is over, so another can be started after it
+    pub fn end(&self) -> Instant {
+        self.start + self.effect.duration()
+    }
+
+    pub fn is_done(&self, now: Instant) -> bool {
+        now >= self.end()
+    }
+
+    /// how far along the animation is, from 0 to 1, or `None` if it hasn't started yet
+    pub fn progress(&self, now: Instant) -> Option<f32> {
+        let elapsed = now.checked_duration_since(self.start)?;
+        Some((elapsed.as_secs_f32() / self.effect.duration().as_secs_f32()).min(1.0))
+    }
+}
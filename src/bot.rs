@@ -0,0 +1,57 @@
+//! the messages passed with a bot: a program of any kind, started with `--bot <command>`,
+//! that plays as the opponent over its stdin and stdout
+//!
+//! each message is one line of ron, like the network messages. a game goes:
+//!
+//! - the game sends `PlaceFleet(lengths: [2, 3, 4, 4, 5], no_touching: false)`, once the
+//!   player has placed their ships
+//! - the bot answers with its fleet, one list of cells per ship, in the same order as the
+//!   lengths, bow first: `Fleet([[(x: 0, y: 0), (x: 1, y: 0)], ...])`
+//! - the player fires first. after every shot, the bot is sent where it landed:
+//!   `ShotResult(by_bot: false, cell: (x: 3, y: 7), hit: true, sunk: false)`
+//! - on the bot's turn, it's sent `YourTurn`, and answers with `Fire((x: 4, y: 2))`.
+//!   firing somewhere it has fired before is ignored, and it's sent `YourTurn` again
+//! - once every ship on one side has sunk, the bot is sent `GameOver(bot_won: true)`.
+//!   a rematch starts over from `PlaceFleet`, otherwise its stdin is closed
+//!
+//! a bot that sends anything else, exits, or takes more than 10 seconds to answer forfeits
+//! the game. it's given half a second to exit once its stdin is closed, before it's killed
+//!
+//! cells go from 0 to 9, with x across and y down. anything the bot writes to stderr is
+//! passed through, for debugging
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::game::Cell;
+
+/// how long a bot has to answer before it's taken to have hung
+pub(crate) const BOT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// messages the game sends to the bot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum ToBot {
+    /// place a fleet of ships with these lengths, by the house rules
+    PlaceFleet {
+        lengths: Vec<u8>,
+        no_touching: bool,
+    },
+    YourTurn,
+    /// where a shot landed, either the bot's or the player's
+    ShotResult {
+        by_bot: bool,
+        cell: Cell,
+        hit: bool,
+        sunk: bool,
+    },
+    GameOver {
+        bot_won: bool,
+    },
+}
+
+/// messages the bot sends back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum FromBot {
+    /// the cells of each ship, bow first
+    Fleet(Vec<Vec<Cell>>),
+    Fire(Cell),
+}
@@ -1,16 +1,167 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::files::load_ron;
+
 // type alias for colors
 pub type Color = [u8; 4];
 
+/// blend a color over a frame pixel, using the color's alpha
+pub(crate) fn blend(pixel: &mut [u8], color: Color) {
+    let alpha = color[3] as u32;
+    for i in 0..3 {
+        pixel[i] = ((pixel[i] as u32 * (255 - alpha) + color[i] as u32 * alpha) / 255) as u8;
+    }
+    pixel[3] = 0xff;
+}
+
 // consts for colors
 pub(crate) const WHITE: Color = [0xff, 0xff, 0xff, 0xff]; // FFFFFF
 pub(crate) const BLACK: Color = [0x00, 0x00, 0x00, 0xff]; // 000000
 pub(crate) const DARK_GREEN: Color = [0x20, 0x2a, 0x25, 0xff]; // 202A25
-pub(crate) const GRAY: Color = [0xeB, 0xe9, 0xe9, 0xff]; //EBE9E9
-pub(crate) const GREEN: Color = [0x00, 0xA8, 0x78, 0xff]; // 00A878
+pub(crate) const GRAY: Color = [0xeb, 0xe9, 0xe9, 0xff]; //EBE9E9
+pub(crate) const GREEN: Color = [0x00, 0xa8, 0x78, 0xff]; // 00A878
 pub(crate) const YELLOW: Color = [0xf8, 0xf3, 0x2b, 0xff]; // F8F32B
 pub(crate) const BLUE: Color = [0x6c, 0xcf, 0xf6, 0xff]; // 6CCFF6
 pub(crate) const FLAME: Color = [0xcf, 0x5c, 0x36, 0xff]; // CF5C36
 
-pub(crate) const GRID_LINES: Color = GRAY;
-pub(crate) const GRID_EMPTY: Color = BLUE;
-pub(crate) const BACKGROUND: Color = DARK_GREEN;
+// pure colors, for the high contrast theme
+const NAVY: Color = [0x00, 0x00, 0x80, 0xff]; // 000080
+const CYAN: Color = [0x00, 0xff, 0xff, 0xff]; // 00FFFF
+const PURE_YELLOW: Color = [0xff, 0xff, 0x00, 0xff]; // FFFF00
+const PURE_GREEN: Color = [0x00, 0xff, 0x00, 0xff]; // 00FF00
+const PURE_RED: Color = [0xff, 0x00, 0x00, 0xff]; // FF0000
+const LIGHT_RED: Color = [0xff, 0x40, 0x40, 0xff]; // FF4040
+const MAGENTA: Color = [0xff, 0x00, 0xff, 0xff]; // FF00FF
+
+// the Okabe-Ito palette, for the colorblind safe theme
+const CHARCOAL: Color = [0x1a, 0x1a, 0x1a, 0xff]; // 1A1A1A
+const OKABE_ORANGE: Color = [0xe6, 0x9f, 0x00, 0xff]; // E69F00
+const OKABE_SKY_BLUE: Color = [0x56, 0xb4, 0xe9, 0xff]; // 56B4E9
+const OKABE_YELLOW: Color = [0xf0, 0xe4, 0x42, 0xff]; // F0E442
+const OKABE_BLUE: Color = [0x00, 0x72, 0xb2, 0xff]; // 0072B2
+const OKABE_VERMILLION: Color = [0xd5, 0x5e, 0x00, 0xff]; // D55E00
+const OKABE_PURPLE: Color = [0xcc, 0x79, 0xa7, 0xff]; // CC79A7
+
+/// what every part of the screen is colored in
+///
+/// the built-in themes are below, and more can be added as a list of themes in
+/// `themes.ron` in the config directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Theme {
+    pub(crate) name: String,
+    pub(crate) background: Color,
+    pub(crate) grid_lines: Color,
+    pub(crate) grid_empty: Color,
+    /// the big "Battleship" and other headings
+    pub(crate) title: Color,
+    pub(crate) text: Color,
+    /// the selected menu entry, and other things that want attention
+    pub(crate) highlight: Color,
+    /// menu entries that aren't selected, and hints
+    pub(crate) muted: Color,
+    pub(crate) error: Color,
+    pub(crate) ship_placing: Color,
+    pub(crate) ship_locked: Color,
+    pub(crate) hit: Color,
+    pub(crate) miss: Color,
+    /// where this player is aiming, on the other player's grid
+    pub(crate) target: Color,
+    /// where the other player is aiming, on this player's grid
+    pub(crate) enemy_target: Color,
+    pub(crate) victory: Color,
+    pub(crate) defeat: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            name: "default".to_string(),
+            background: DARK_GREEN,
+            grid_lines: GRAY,
+            grid_empty: BLUE,
+            title: GREEN,
+            text: WHITE,
+            highlight: YELLOW,
+            muted: GREEN,
+            error: FLAME,
+            ship_placing: YELLOW,
+            ship_locked: GREEN,
+            hit: BLACK,
+            miss: WHITE,
+            target: FLAME,
+            enemy_target: YELLOW,
+            victory: GREEN,
+            defeat: FLAME,
+        }
+    }
+}
+
+impl Theme {
+    /// black and white, with pure primaries for everything that matters
+    pub(crate) fn high_contrast() -> Self {
+        Theme {
+            name: "high contrast".to_string(),
+            background: BLACK,
+            grid_lines: WHITE,
+            grid_empty: NAVY,
+            title: CYAN,
+            text: WHITE,
+            highlight: PURE_YELLOW,
+            muted: CYAN,
+            error: LIGHT_RED,
+            ship_placing: PURE_YELLOW,
+            ship_locked: PURE_GREEN,
+            hit: PURE_RED,
+            miss: WHITE,
+            target: MAGENTA,
+            enemy_target: PURE_YELLOW,
+            victory: PURE_GREEN,
+            defeat: LIGHT_RED,
+        }
+    }
+
+    /// the Okabe-Ito palette, which stays apart for deuteranopia and protanopia:
+    /// nothing is told apart by red against green alone
+    pub(crate) fn colorblind() -> Self {
+        Theme {
+            name: "colorblind safe".to_string(),
+            background: CHARCOAL,
+            grid_lines: GRAY,
+            grid_empty: OKABE_SKY_BLUE,
+            title: OKABE_ORANGE,
+            text: WHITE,
+            highlight: OKABE_YELLOW,
+            muted: OKABE_SKY_BLUE,
+            error: OKABE_ORANGE,
+            ship_placing: OKABE_YELLOW,
+            ship_locked: OKABE_BLUE,
+            hit: BLACK,
+            miss: WHITE,
+            target: OKABE_VERMILLION,
+            enemy_target: OKABE_PURPLE,
+            victory: OKABE_SKY_BLUE,
+            defeat: OKABE_ORANGE,
+        }
+    }
+
+    fn path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_default()
+            .join("battleship")
+            .join("themes.ron")
+    }
+
+    pub(crate) fn built_in() -> Vec<Theme> {
+        vec![
+            Theme::default(),
+            Theme::high_contrast(),
+            Theme::colorblind(),
+        ]
+    }
+
+    /// the themes from the config file, if there are any
+    pub(crate) fn load_user_themes() -> Result<Vec<Theme>, String> {
+        Ok(load_ron(&Theme::path())?.unwrap_or_default())
+    }
+}
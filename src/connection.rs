@@ -1,15 +1,35 @@
 use std::io::{self, BufRead, Write};
 use std::net::TcpListener;
 use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
 
 static HOST: &str = "127.0.0.1";
 static PORT: &str = "7878";
+/// how long a bot has to exit by itself once its stdin is closed, before it's killed
+const BOT_EXIT_TIME: Duration = Duration::from_millis(500);
 
 // Borrowed LinesCodec from https://thepacketgeek.com/rust/tcpstream/lines-codec/
 pub struct LinesCodec {
     // Our buffered reader & writers
-    reader: io::BufReader<TcpStream>,
-    writer: io::LineWriter<TcpStream>,
+    // (over a TcpStream, or a bot's stdout and stdin)
+    reader: Incoming,
+    writer: Box<dyn Write>,
+    // whether we listened for the connection, rather than connecting to someone else
+    pub host: bool,
+    // the bot's process, to be waited on once it's done with
+    child: Option<Child>,
+}
+
+/// where received lines come from
+enum Incoming {
+    /// read as they're asked for, waiting as long as it takes
+    Reader(Box<dyn BufRead>),
+    /// read on a thread of their own, so the game can carry on while a bot thinks, and give up
+    /// on one that stops answering
+    Lines(Receiver<io::Result<String>>),
 }
 
 /// Encapsulate a TcpStream with buffered reader/writer functionality
@@ -19,27 +39,104 @@ impl LinesCodec {
         // We can clone the stream to simulate splitting Tx & Rx with `try_clone()`
         let writer = io::LineWriter::new(stream.try_clone()?);
         let reader = io::BufReader::new(stream);
-        Ok(Self { reader, writer })
+        Ok(Self {
+            reader: Incoming::Reader(Box::new(reader)),
+            writer: Box::new(writer),
+            host: false,
+            child: None,
+        })
+    }
+
+    /// start a program, like "python3 bot.py", and pass lines over its stdin and stdout
+    pub fn spawn(command: &str) -> io::Result<Self> {
+        let mut parts = command.split_whitespace();
+        let program = parts
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no command given"))?;
+        let mut child = Command::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        // both are there, since they were piped
+        let writer = io::LineWriter::new(child.stdin.take().unwrap());
+        let reader = io::BufReader::new(child.stdout.take().unwrap());
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let failed = line.is_err();
+                // (stop once nobody's listening)
+                if sender.send(line).is_err() || failed {
+                    return;
+                }
+            }
+        });
+        Ok(Self {
+            reader: Incoming::Lines(lines),
+            writer: Box::new(writer),
+            host: false,
+            child: Some(child),
+        })
+    }
+}
+
+impl Drop for LinesCodec {
+    /// close the bot's stdin, which tells it to exit, and reap it, killing it if it doesn't
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            self.writer = Box::new(io::sink());
+            let start = Instant::now();
+            while let Ok(None) = child.try_wait() {
+                if start.elapsed() > BOT_EXIT_TIME {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return;
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+        }
     }
 }
 
 impl LinesCodec {
     /// Write the given message (appending a newline) to the TcpStream
     pub fn send_message(&mut self, message: &str) -> io::Result<()> {
-        self.writer.write(&message.as_bytes())?;
+        self.writer.write_all(message.as_bytes())?;
         // This will also signal a `writer.flush()` for us; thanks LineWriter!
-        self.writer.write(&['\n' as u8])?;
+        self.writer.write_all(b"\n")?;
         Ok(())
     }
 
     /// Read a received message from the TcpStream
     pub fn read_message(&mut self) -> io::Result<String> {
+        let reader = match &mut self.reader {
+            Incoming::Reader(reader) => reader,
+            Incoming::Lines(lines) => return lines.recv().unwrap_or_else(|_| Err(stopped())),
+        };
         let mut line = String::new();
         // Use `BufRead::read_line()` to read a line from the TcpStream
-        self.reader.read_line(&mut line)?;
+        reader.read_line(&mut line)?;
         line.pop(); // Remove the trailing "\n"
         Ok(line)
     }
+
+    /// A received message, if one has come in yet, without waiting for a bot's next line
+    /// (a TcpStream is still read as it's asked for)
+    pub fn try_read_message(&mut self) -> io::Result<Option<String>> {
+        match &mut self.reader {
+            Incoming::Reader(_) => self.read_message().map(Some),
+            Incoming::Lines(lines) => match lines.try_recv() {
+                Ok(line) => line.map(Some),
+                Err(TryRecvError::Empty) => Ok(None),
+                Err(TryRecvError::Disconnected) => Err(stopped()),
+            },
+        }
+    }
+}
+
+/// the error for a bot that's closed its stdout, which it only does on exiting
+fn stopped() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "it stopped writing to stdout")
 }
 
 pub(crate) fn try_connect() -> std::io::Result<LinesCodec> {
@@ -53,14 +150,15 @@ pub(crate) fn try_connect() -> std::io::Result<LinesCodec> {
 
 pub(crate) fn listen() -> std::io::Result<LinesCodec> {
     let address = format!("{}:{}", HOST, PORT);
-    let listener =
-        TcpListener::bind(&address).expect(&format!("Could not bind to address: {}", &address));
+    let listener = TcpListener::bind(&address)?;
     println!("listening at {}", address);
 
     match listener.accept() {
         Ok((stream, addr)) => {
             println!("accepted connection from {}", addr);
-            LinesCodec::new(stream)
+            let mut codec = LinesCodec::new(stream)?;
+            codec.host = true;
+            Ok(codec)
         }
         Err(e) => Err(e),
     }
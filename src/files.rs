@@ -0,0 +1,28 @@
+//! reading and writing the settings, stats and games kept between runs
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// read a ron file, or nothing if there isn't one yet
+pub(crate) fn load_ron<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(_) => return Ok(None),
+    };
+    ron::de::from_str(&contents)
+        .map(Some)
+        .map_err(|e| format!("could not read {}, {}", path.display(), e))
+}
+
+/// write a ron file, making the directory for it if there isn't one yet
+pub(crate) fn save_ron<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let pretty = ron::ser::PrettyConfig::new();
+    path.parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| {
+            let contents = ron::ser::to_string_pretty(value, pretty).unwrap();
+            fs::write(path, contents)
+        })
+        .map_err(|e| format!("could not save {}, {}", path.display(), e))
+}
@@ -1,55 +1,169 @@
-use rand::prelude::Distribution;
-use rand::{distributions::Standard, rngs::ThreadRng, thread_rng, Rng};
-use rusttype::{point, Font, Scale};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rusttype::Font;
 use serde::{Deserialize, Serialize};
 use std::convert::From;
-use std::iter::{once, repeat};
+use std::fs;
+use std::io;
+use std::iter::repeat_n;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Instant;
 use winit::event::VirtualKeyCode;
-use winit_input_helper::WinitInputHelper;
+use winit_input_helper::TextChar;
 
+use crate::animation::*;
+use crate::bot::*;
 use crate::colors::*;
 use crate::connection::*;
+use crate::files::{load_ron, save_ron};
+use crate::glyphs::*;
+use crate::keys::*;
+use crate::layout::*;
+use crate::scene::*;
+use crate::shapes::*;
+use crate::sprites::*;
+use crate::stats::*;
+use crate::strategy::*;
+use crate::tui;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 enum GameResult {
     Victory,
     Defeat,
+    /// hotseat games have no 'us' and 'them', just player 1 and player 2
+    PlayerWon(u8),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum GameType {
     Ai,
     LocalNetwork,
+    Hotseat,
+    /// against a program started with `--bot`
+    ExternalBot,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 enum GameStatus {
     Starting,
+    KeyBindings,
     Playing(GameType),
     End(GameResult),
+    /// stepping through recorded games
+    Replay,
+    Stats,
+}
+
+/// house rules, picked in the settings and agreed on before the game starts
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub(crate) struct Rules {
+    /// ships may not touch each other, not even diagonally
+    pub(crate) no_touching: bool,
+}
+
+/// entries on the start screen, in the order they are drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MenuItem {
+    /// pick up the saved game, if there is one
+    Continue(GameType),
+    Start(GameType),
+    Replays,
+    Stats,
+    /// which computer player to play against
+    Strategy,
+    NoTouching,
+    KeyBindings,
+    Theme,
+    Animations,
+    FocusedView,
+}
+
+const MENU: [MenuItem; 12] = [
+    MenuItem::Start(GameType::LocalNetwork),
+    MenuItem::Start(GameType::Ai),
+    MenuItem::Start(GameType::Hotseat),
+    MenuItem::Start(GameType::ExternalBot),
+    MenuItem::Replays,
+    MenuItem::Stats,
+    MenuItem::Strategy,
+    MenuItem::NoTouching,
+    MenuItem::Theme,
+    MenuItem::Animations,
+    MenuItem::FocusedView,
+    MenuItem::KeyBindings,
+];
+
+/// what can be done once a game is over, in the order drawn on the end screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EndChoice {
+    /// play the same kind of game again, with the same rules
+    Rematch,
+    /// go over the game just played in the replay viewer
+    WatchReplay,
+    Menu,
+}
+
+/// what each side of a network game picked once it was over. both send theirs, so a rematch
+/// only starts if both want one
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum AfterGame {
+    Rematch,
+    Leave,
+}
+
+const END_CHOICES: [EndChoice; 3] = [EndChoice::Rematch, EndChoice::WatchReplay, EndChoice::Menu];
+// end screen placement, in design coordinates
+const END_CHOICES_TOP: f32 = 95.0;
+const END_CHOICE_WIDTH: f32 = 200.0;
+
+/// the ui state of the end screen
+#[derive(Debug)]
+struct EndScreen {
+    /// the game that just ended
+    game_type: GameType,
+    /// index into `END_CHOICES`
+    selected: usize,
+    /// the file the game's replay was written to
+    replay: Option<PathBuf>,
+    /// network games: rematch was picked, and the other player is still to answer
+    waiting_for_rematch: bool,
+    /// network games: the other player left rather than play again
+    other_left: bool,
+    /// bot games: what the bot did wrong, if it lost by breaking the protocol
+    forfeit: Option<String>,
 }
 
 /// represents the settings ui, before the game has fully started
 /// on game start, GameType becomes part of the GameStatus::Playing enum variant
 struct Settings {
-    game_type: GameType,
+    /// index into `World::menu`
+    selected: usize,
+    rules: Rules,
+    /// why the last game couldn't start, if it couldn't
+    error: Option<String>,
+    /// index into `STRATEGIES`, for who to play against in games vs. the computer
+    strategy: usize,
 }
 
 // 0-indexed grid positions
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
-struct Cell {
-    x: u8,
-    y: u8,
+pub(crate) struct Cell {
+    pub(crate) x: u8,
+    pub(crate) y: u8,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-enum Action {
+pub(crate) enum Action {
     Enter,
     Space,
     Right,
     Left,
     Up,
     Down,
+    /// fire straight at a cell, rather than walking the target there
+    FireAt(Cell),
+    /// in focused view, look at the grid that isn't in use
+    Peek,
 }
 
 impl From<Direction> for Action {
@@ -77,26 +191,42 @@ impl Cell {
         valid_shift
     }
 
-    fn random_seq(len: &u8, rng: &mut ThreadRng) -> Vec<Cell> {
-        let cell: Cell = rng.gen();
-        cell.extend_random_direction(rng, len)
+    /// true for the same cell, or any of the eight cells around it
+    pub(crate) fn touches(&self, other: &Cell) -> bool {
+        (self.x as i8 - other.x as i8).abs() <= 1 && (self.y as i8 - other.y as i8).abs() <= 1
     }
 
-    fn extend_random_direction(&self, rng: &mut ThreadRng, len: &u8) -> Vec<Cell> {
-        let mut cell = self.clone();
-        let mut res = Vec::new();
-        let mut valid = false;
-        while !valid {
-            valid = true;
-            // for any valid cell, extending len in _some_ direction should be valid
-            let direction = rng.gen();
-            for _i in 0..*len {
-                res.push(cell.clone());
-                // ensure all cells are valid shifts
-                valid = valid && cell.shift(&direction);
-            }
-        }
-        res
+    /// read a cell the way the grids are labelled: row letter, then column number, like "B7"
+    fn parse(text: &str) -> Result<Cell, String> {
+        let mut chars = text.chars();
+        let row = match chars.next().map(|c| c.to_ascii_uppercase()) {
+            Some(letter @ 'A'..='J') => letter as u8 - b'A',
+            _ => return Err(format!("{} should start with a letter from A to J", text)),
+        };
+        let column = match chars.as_str().parse::<u8>() {
+            Ok(number @ 1..=10) => number - 1,
+            _ => return Err(format!("{} should end with a number from 1 to 10", text)),
+        };
+        Ok(Cell { x: column, y: row })
+    }
+
+    /// the arrow presses that walk from this cell to the other one
+    fn directions_to(&self, other: &Cell) -> Vec<Direction> {
+        let horizontal = if other.x > self.x {
+            Direction::Right
+        } else {
+            Direction::Left
+        };
+        let vertical = if other.y > self.y {
+            Direction::Down
+        } else {
+            Direction::Up
+        };
+        let x_steps = (other.x as i8 - self.x as i8).unsigned_abs() as usize;
+        let y_steps = (other.y as i8 - self.y as i8).unsigned_abs() as usize;
+        repeat_n(horizontal, x_steps)
+            .chain(repeat_n(vertical, y_steps))
+            .collect()
     }
 
     fn seq_from_origin(len: u8) -> Vec<Cell> {
@@ -115,15 +245,6 @@ impl Cell {
     }
 }
 
-impl Distribution<Cell> for Standard {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Cell {
-        Cell {
-            x: rng.gen_range(0..10),
-            y: rng.gen_range(0..10),
-        }
-    }
-}
-
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 enum ShipStatus {
     #[default]
@@ -132,7 +253,7 @@ enum ShipStatus {
     Locked,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 enum Direction {
     Up,
     Down,
@@ -140,20 +261,6 @@ enum Direction {
     Right,
 }
 
-impl Distribution<Direction> for Standard {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Direction {
-        use Direction::*;
-        let index: u8 = rng.gen_range(0..4);
-        match index {
-            0 => Up,
-            1 => Down,
-            2 => Left,
-            3 => Right,
-            _ => unreachable!(),
-        }
-    }
-}
-
 impl Direction {
     fn xy(&self) -> (i8, i8) {
         use Direction::*;
@@ -166,6 +273,11 @@ impl Direction {
     }
 }
 
+/// what each ship in a fleet is called, in the order they're placed
+const SHIP_NAMES: [&str; 5] = ["destroyer", "submarine", "cruiser", "battleship", "carrier"];
+/// and how long each one is
+const SHIP_LENGTHS: [u8; 5] = [2, 3, 4, 4, 5];
+
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 struct Ship {
     status: ShipStatus,
@@ -174,32 +286,65 @@ struct Ship {
 }
 
 impl Ship {
-    fn random_five(rng: &mut ThreadRng) -> Vec<Self> {
-        use ShipStatus::*;
-        let mut ships = Vec::new();
-        for len in [2, 3, 4, 4, 5].iter() {
-            let cells = Cell::random_seq(len, rng);
-            let mut ship = Ship {
-                status: Locked,
-                len: *len,
-                cells,
-            };
-            while Ship::any_overlap(&ship, &ships) {
-                let cells = Cell::random_seq(len, rng);
-                ship = Ship {
-                    status: Locked,
-                    len: *len,
-                    cells,
-                };
-            }
-            ships.push(ship);
+    /// a placed ship from its cells, bow first, if they make a straight, unbroken line on the grid
+    fn from_cells(cells: Vec<Cell>) -> Result<Self, String> {
+        if cells.len() < 2 {
+            return Err(format!(
+                "a ship needs at least 2 cells, not {}",
+                cells.len()
+            ));
+        }
+        if let Some(cell) = cells
+            .iter()
+            .find(|cell| cell.x as usize >= CELL_COUNT || cell.y as usize >= CELL_COUNT)
+        {
+            return Err(format!("({}, {}) is off the grid", cell.x, cell.y));
         }
-        ships
+        let step = |a: &Cell, b: &Cell| (b.x as i8 - a.x as i8, b.y as i8 - a.y as i8);
+        let first_step = step(&cells[0], &cells[1]);
+        let straight = [(0, 1), (0, -1), (1, 0), (-1, 0)].contains(&first_step)
+            && cells
+                .windows(2)
+                .all(|pair| step(&pair[0], &pair[1]) == first_step);
+        if !straight {
+            return Err(format!("{:?} isn't a straight line of cells", cells));
+        }
+        Ok(Ship {
+            status: ShipStatus::Locked,
+            len: cells.len() as u8,
+            cells,
+        })
+    }
+
+    /// a whole fleet from the cells of each ship, if it has ships of these lengths, in this
+    /// order, that could have been placed by hand
+    fn fleet(cells: Vec<Vec<Cell>>, lengths: &[u8], rules: &Rules) -> Result<Vec<Self>, String> {
+        let ships = cells
+            .into_iter()
+            .map(Ship::from_cells)
+            .collect::<Result<Vec<Ship>, String>>()?;
+        let ship_lengths: Vec<u8> = ships.iter().map(|ship| ship.len).collect();
+        if ship_lengths != lengths {
+            return Err(format!(
+                "the ships are {:?} long, but should be {:?}",
+                ship_lengths, lengths
+            ));
+        }
+        if let Some(ship) = ships
+            .iter()
+            .find(|ship| Ship::conflicts(ship, &ships, rules))
+        {
+            return Err(format!(
+                "the ship at {:?} is on top of, or touching, another",
+                ship.cells
+            ));
+        }
+        Ok(ships)
     }
 
     fn original_length_ships() -> Vec<Self> {
         use ShipStatus::*;
-        let mut res: Vec<Self> = [2, 3, 4, 4, 5]
+        let mut res: Vec<Self> = SHIP_LENGTHS
             .iter()
             .map(|&len| Ship {
                 status: Hidden,
@@ -211,6 +356,43 @@ impl Ship {
         res
     }
 
+    /// the sprite for one of this ship's cells: the first cell is the bow, the last the stern
+    fn sprite(&self, cell: &Cell) -> Shape {
+        let index = self.cells.iter().position(|c| c == cell).unwrap_or(0);
+        let part = match index {
+            0 => Part::Bow,
+            i if i == self.cells.len() - 1 => Part::Stern,
+            _ => Part::Mid,
+        };
+        // the bow points away from the rest of the ship, or up if there's no rest of it
+        let (bow, next) = match (self.cells.first(), self.cells.get(1)) {
+            (Some(bow), Some(next)) => (bow, next),
+            _ => return Shape::Sprite { part, turns: 0 },
+        };
+        let turns = match (bow.x as i8 - next.x as i8, bow.y as i8 - next.y as i8) {
+            (0, -1) => 0,
+            (1, 0) => 1,
+            (0, 1) => 2,
+            _ => 3,
+        };
+        Shape::Sprite { part, turns }
+    }
+
+    /// the hull shape for one of this ship's cells, joined to the ship's cells next to it
+    fn hull(&self, cell: &Cell) -> Shape {
+        let joins = |dx: i8, dy: i8| {
+            self.cells
+                .iter()
+                .any(|c| c.x as i8 - cell.x as i8 == dx && c.y as i8 - cell.y as i8 == dy)
+        };
+        Shape::Hull {
+            up: joins(0, -1),
+            down: joins(0, 1),
+            left: joins(-1, 0),
+            right: joins(1, 0),
+        }
+    }
+
     fn shift(&mut self, direction: &Direction) {
         let (x, y) = direction.xy();
         // move each cell in the direction it should be moved
@@ -274,25 +456,47 @@ impl Ship {
         }
     }
 
+    /// whether the ship is on top of any of the others (itself aside, if it's one of them)
     fn any_overlap(ship: &Ship, ships: &[Ship]) -> bool {
         ships.iter().any(|other_ship| {
             other_ship.status != ShipStatus::Hidden
-                && other_ship != ship
+                && !std::ptr::eq(other_ship, ship)
                 && other_ship
                     .cells
                     .iter()
                     .any(|cell| ship.cells.contains(cell))
         })
     }
+
+    fn any_touching(ship: &Ship, ships: &[Ship]) -> bool {
+        ships.iter().any(|other_ship| {
+            other_ship.status != ShipStatus::Hidden
+                && !std::ptr::eq(other_ship, ship)
+                && other_ship
+                    .cells
+                    .iter()
+                    .any(|cell| ship.cells.iter().any(|c| c.touches(cell)))
+        })
+    }
+
+    /// whether the ship can't go where it is, given the other ships and the house rules
+    fn conflicts(ship: &Ship, ships: &[Ship], rules: &Rules) -> bool {
+        if rules.no_touching {
+            Ship::any_touching(ship, ships)
+        } else {
+            Ship::any_overlap(ship, ships)
+        }
+    }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 enum PlayerStatus {
     Placing,
     Aiming,
     Waiting,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Player {
     status: PlayerStatus,
     ships: Vec<Ship>,
@@ -330,13 +534,13 @@ impl Player {
         !overlaps_shot
     }
 
-    fn lock_ship(&mut self) -> bool {
-        let overlap = match self.ship_to_place() {
-            Some(ship) => Ship::any_overlap(&ship, &self.ships),
-            None => false
+    fn lock_ship(&mut self, rules: &Rules) -> bool {
+        let conflict = match self.ship_to_place() {
+            Some(ship) => Ship::conflicts(ship, &self.ships, rules),
+            None => false,
         };
 
-        if !overlap {
+        if !conflict {
             if let Some(ship) = self.ship_to_place_mut() {
                 ship.status = ShipStatus::Locked;
                 return true;
@@ -346,15 +550,205 @@ impl Player {
     }
 }
 
-// frame size consts
-pub const WIDTH: u32 = 720;
-pub const HEIGHT: u32 = 600;
-const TOP_MARGIN: usize = 190;
-const GRID_WIDTH: usize = 301;
-const GRID_MARGIN: usize = 40;
-const CELL_WIDTH: usize = 30;
-const CELL_MARGIN: usize = 4;
-const CELL_COUNT: usize = 10;
+pub(crate) const CELL_COUNT: usize = 10;
+// menu placement, in design coordinates
+const MENU_TOP: f32 = 60.0;
+const MENU_ROW_HEIGHT: f32 = 34.0;
+
+/// everything needed to pick a game up where it was left off, saved as ron in the data directory
+///
+/// network games can't be saved, since the connection can't be
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedGame {
+    status: GameStatus,
+    this_player: Player,
+    other_player: Player,
+    rules: Rules,
+    player_number: u8,
+    /// the rng is seeded with this on resuming, so the ai carries on just the same
+    seed: u64,
+    /// the game so far, so its replay covers the whole game
+    #[serde(default)]
+    recording: Option<Replay>,
+    /// the name of the computer player, in games against it
+    #[serde(default)]
+    strategy: Option<String>,
+}
+
+impl SavedGame {
+    fn path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_default()
+            .join("battleship")
+            .join("save.ron")
+    }
+
+    /// the saved game, if there is one
+    fn load() -> Result<Option<SavedGame>, String> {
+        load_ron(&SavedGame::path())
+    }
+
+    fn save(&self) -> Result<(), String> {
+        save_ron(&SavedGame::path(), self)
+    }
+
+    /// forget the saved game, once it's over
+    fn delete() {
+        // (there may not have been one)
+        let _ = fs::remove_file(SavedGame::path());
+    }
+}
+
+/// how the game looks, saved as ron in the config directory next to the key bindings
+#[derive(Debug, Serialize, Deserialize)]
+struct Preferences {
+    /// the name of the theme, since user themes can be added or moved around
+    theme: String,
+    animate: bool,
+    focused_view: bool,
+}
+
+impl Preferences {
+    fn path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_default()
+            .join("battleship")
+            .join("preferences.ron")
+    }
+}
+
+/// a whole game: both fleets, and every action aimed at them in the order they happened.
+/// replaying the actions with the real rules gets back every shot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Replay {
+    game_type: GameType,
+    /// player 1's fleet, then player 2's. filled in once the game is over
+    fleets: [Vec<Ship>; 2],
+    /// each batch of actions aimed at the other fleet, and which player (1 or 2) took it
+    moves: Vec<(u8, Vec<Action>)>,
+}
+
+impl Replay {
+    fn new(game_type: GameType) -> Self {
+        Replay {
+            game_type,
+            fleets: Default::default(),
+            moves: Vec::new(),
+        }
+    }
+
+    fn dir() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_default()
+            .join("battleship")
+            .join("replays")
+    }
+
+    /// a new file for a game that just finished, named for when it did
+    fn new_path() -> PathBuf {
+        let since_epoch = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        let secs = since_epoch.as_secs();
+        let mut path = Replay::dir().join(format!("{}.ron", secs));
+        // games that end within the same second get a number after the time
+        let mut n = 1;
+        while path.exists() {
+            n += 1;
+            path = Replay::dir().join(format!("{}-{}.ron", secs, n));
+        }
+        path
+    }
+
+    /// every recorded game, newest first
+    fn all() -> Vec<PathBuf> {
+        let entries = match fs::read_dir(Replay::dir()) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+        let mut files: Vec<(std::time::SystemTime, PathBuf)> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == "ron"))
+            .map(|path| {
+                let modified = fs::metadata(&path).and_then(|m| m.modified());
+                (modified.unwrap_or(std::time::UNIX_EPOCH), path)
+            })
+            .collect();
+        files.sort_by(|a, b| b.cmp(a));
+        files.into_iter().map(|(_, path)| path).collect()
+    }
+
+    fn load(path: &Path) -> Result<Option<Replay>, String> {
+        load_ron(path)
+    }
+
+    fn save(&self, path: &Path) -> Result<(), String> {
+        save_ron(path, self)
+    }
+
+    /// both players before the first shot, then after each shot, player 1 first
+    fn turns(&self) -> Vec<[Player; 2]> {
+        let mut players = [Player::new(), Player::new()];
+        for (player, fleet) in players.iter_mut().zip(self.fleets.iter()) {
+            player.ships = fleet.clone();
+            player.status = PlayerStatus::Waiting;
+        }
+        let mut turns = vec![players.clone()];
+        for (number, actions) in &self.moves {
+            let [one, two] = &mut players;
+            let (shooter, target) = if *number == 1 { (one, two) } else { (two, one) };
+            let shots = shooter.shots_taken.len();
+            World::aim(actions, shooter, target);
+            if shooter.shots_taken.len() > shots {
+                turns.push(players.clone());
+            }
+        }
+        turns
+    }
+}
+
+/// the ui state of the replay screen
+#[derive(Debug, Default)]
+struct ReplayViewer {
+    /// the replay files, newest first, and the index of the one being watched
+    files: Vec<PathBuf>,
+    file: usize,
+    /// the replay being watched, or `None` if there are none or it couldn't be read
+    replay: Option<Replay>,
+    /// both players after each turn, from `Replay::turns`, and the turn on screen
+    turns: Vec<[Player; 2]>,
+    turn: usize,
+}
+
+impl ReplayViewer {
+    fn open() -> Self {
+        let mut viewer = ReplayViewer {
+            files: Replay::all(),
+            ..ReplayViewer::default()
+        };
+        viewer.load();
+        viewer
+    }
+
+    /// read the selected replay, and start it from the beginning
+    fn load(&mut self) {
+        self.replay = self
+            .files
+            .get(self.file)
+            .and_then(|path| Replay::load(path).ok().flatten());
+        self.turns = self.replay.as_ref().map_or_else(Vec::new, Replay::turns);
+        self.turn = 0;
+    }
+}
+
+/// what was read, or the default if it couldn't be, with why kept to show the player
+fn or_default<T: Default>(result: Result<T, String>, notices: &mut Vec<String>) -> T {
+    result.unwrap_or_else(|e| {
+        notices.push(e);
+        T::default()
+    })
+}
 
 /// Representation of the application state, plus some helpers (font, rng, tcp stream)
 pub struct World<'a> {
@@ -362,62 +756,383 @@ pub struct World<'a> {
     this_player: Player,
     other_player: Player,
     settings: Option<Settings>,
+    /// a game left unfinished last time, which can be continued from the start screen
+    saved_game: Option<SavedGame>,
+    rules: Rules,
+    /// hotseat: which human has the device, and is therefore `this_player` (1 or 2)
+    player_number: u8,
+    /// hotseat: hide the boards until the next player has the device
+    privacy_screen: bool,
+    /// placing: the cell under the mouse while a ship is being dragged around
+    drag: Option<Cell>,
+    bindings: KeyBindings,
+    key_screen: KeyBindingScreen,
+    /// the game being played, recorded as it goes
+    recording: Option<Replay>,
+    replay_viewer: ReplayViewer,
+    /// set when a game ends
+    end_screen: Option<EndScreen>,
+    /// how every finished game has gone
+    stats: Stats,
+    /// the built-in and user themes, and the index of the one in use
+    themes: Vec<Theme>,
+    theme: usize,
+    /// shots landing, which have to play out before anything else happens
+    animations: Vec<Animation>,
+    /// off to skip animations, and have every shot land straight away
+    animate: bool,
+    /// the time as of the last update, which animations are drawn for
+    now: Instant,
+    /// show one big grid at a time, rather than both side by side
+    focused_view: bool,
+    /// focused view: show the grid that isn't in use right now
+    peek: bool,
+    /// focused view: the grid in use as of the last frame. `true` for this player's
+    active_grid: bool,
+    /// aiming: a cell typed in by hand, like "B7"
+    typed_cell: String,
+    typed_cell_error: Option<String>,
+    layout: Layout,
+    /// what's in the frame buffer right now, if anything
+    last_scene: Option<Scene>,
+    full_redraw: bool,
     font: Font<'a>,
-    rng: ThreadRng,
+    glyphs: GlyphCache,
+    /// ship bitmaps, or `None` to draw plain hulls if they couldn't be loaded
+    sprites: Option<ShipSprites>,
+    /// what's gone wrong that the player should know about, shown until they next press a key
+    notices: Vec<String>,
+    rng: StdRng,
+    /// how the computer plays, in games against it
+    strategy: Box<dyn Strategy>,
+    /// the computer's moves still to make on its turn, last first
+    ai_moves: Vec<Action>,
+    /// the connection to the other player over the network, or to the bot
     stream: Option<LinesCodec>,
+    bot_command: Option<String>,
+    /// when the bot was asked for something it hasn't answered yet
+    bot_asked: Option<Instant>,
 }
 
 impl World<'_> {
     /// render the `World` state to the frame buffer.
-    pub fn render(&self, frame: &mut [u8]) {
-        World::clear_top(frame);
-        World::clear_grids(frame);
-        World::clear_bottom(frame);
+    ///
+    /// the frame keeps what was drawn last time, so only what changed since then is redrawn
+    pub fn render(&mut self, frame: &mut [u8]) {
+        self.focus();
+        let scene = self.scene();
+        match self.last_scene.take() {
+            Some(last) if !self.full_redraw && last.grids == scene.grids => {
+                self.draw_changes(frame, &last, &scene)
+            }
+            _ => self.draw_scene(frame, &scene),
+        }
+        self.last_scene = Some(scene);
+    }
+
+    /// render the `World` state as lines of text with ansi colors, for the terminal
+    pub fn render_text(&mut self) -> Vec<String> {
+        self.focus();
+        let scene = self.scene();
+        tui::draw(&scene, &self.layout, self.theme())
+    }
+
+    /// always redraw the whole frame, e.g. to compare frame times
+    pub fn set_full_redraw(&mut self, full_redraw: bool) {
+        self.full_redraw = full_redraw;
+    }
+
+    /// in focused view, lay out just the grid in use, or the other one when peeking
+    fn focus(&mut self) {
+        let active_grid = match self.animations.first() {
+            // shots are watched landing, wherever they land
+            Some(animation) => animation.this_player,
+            None => self.this_player.status != PlayerStatus::Aiming,
+        };
+        if active_grid != self.active_grid {
+            // a peek only lasts until it's the other grid's turn anyway
+            self.active_grid = active_grid;
+            self.peek = false;
+        }
+        let focus = match self.status {
+            GameStatus::Playing(_) if self.focused_view => Some(self.active_grid != self.peek),
+            _ => None,
+        };
+        if self.layout.focus != focus {
+            self.layout.focus = focus;
+            // the grids have moved, so nothing drawn so far can be kept
+            self.last_scene = None;
+        }
+    }
 
+    /// everything on screen for the current state
+    fn scene(&self) -> Scene {
+        let mut scene = Scene::default();
         match self.status {
-            GameStatus::Starting => self.draw_start_screen(frame),
+            GameStatus::Starting => self.draw_start_screen(&mut scene),
+            GameStatus::KeyBindings => self.draw_key_bindings(&mut scene),
+            GameStatus::Playing(GameType::Hotseat) if self.privacy_screen => {
+                self.draw_privacy_screen(&mut scene)
+            }
+            GameStatus::Playing(_) if self.needs_connection() => self.draw_connecting(&mut scene),
             GameStatus::Playing(_) => {
-                self.draw_ships(frame);
-                self.draw_shots(frame);
-                self.draw_target(frame);
-                self.draw_info(frame);
+                scene.grids = true;
+                self.draw_ships(&mut scene);
+                self.draw_shots(&mut scene);
+                self.draw_target(&mut scene);
+                self.draw_animations(&mut scene);
+                self.draw_info(&mut scene);
+                // nothing is drawn on a grid that's not on screen
+                scene
+                    .cells
+                    .retain(|(_, this_player, _, _)| self.layout.shows(*this_player));
             }
-            GameStatus::End(_) => self.draw_end_message(frame),
+            GameStatus::End(_) => {
+                scene.grids = true;
+                self.draw_end_screen(&mut scene);
+            }
+            GameStatus::Replay => self.draw_replay(&mut scene),
+            GameStatus::Stats => self.draw_stats(&mut scene),
+        }
+        // along the bottom, the latest last
+        let theme = self.theme();
+        for (i, notice) in self.notices.iter().rev().enumerate() {
+            let y = DESIGN_HEIGHT as f32 - 24.0 * (i + 1) as f32;
+            scene.text(notice, theme.error, 16.0, (20.0, y));
         }
+        scene
     }
 
     /// Create a new `World` instance with empty values
-    pub fn new() -> Self {
+    pub fn new(layout: Layout) -> Self {
         let font_data = include_bytes!("../assets/source-code-pro-regular.ttf");
         let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        // anything that couldn't be read, to tell the player once they can see it
+        let mut notices = Vec::new();
+        let sprites = ShipSprites::load()
+            .map_err(|e| {
+                notices.push(format!(
+                    "could not load the ship sprites, drawing plain hulls: {}",
+                    e
+                ))
+            })
+            .ok();
+        let mut themes = Theme::built_in();
+        themes.extend(or_default(Theme::load_user_themes(), &mut notices));
 
-        World {
+        let mut world = World {
             this_player: Player::new(),
             other_player: Player::new(),
             status: GameStatus::Starting,
             font,
             stream: None,
-            rng: thread_rng(),
+            bot_command: None,
+            bot_asked: None,
+            rng: StdRng::from_entropy(),
+            strategy: default_strategy(),
+            ai_moves: Vec::new(),
             settings: Some(Settings {
-                game_type: GameType::LocalNetwork,
+                selected: 0,
+                rules: Rules::default(),
+                error: None,
+                strategy: 0,
             }),
+            saved_game: or_default(SavedGame::load(), &mut notices),
+            rules: Rules::default(),
+            player_number: 1,
+            privacy_screen: false,
+            drag: None,
+            bindings: or_default(KeyBindings::load(), &mut notices),
+            key_screen: KeyBindingScreen::default(),
+            recording: None,
+            replay_viewer: ReplayViewer::default(),
+            end_screen: None,
+            stats: or_default(Stats::load(), &mut notices),
+            themes,
+            theme: 0,
+            animations: Vec::new(),
+            animate: true,
+            now: Instant::now(),
+            focused_view: false,
+            peek: false,
+            active_grid: true,
+            typed_cell: String::new(),
+            typed_cell_error: None,
+            layout,
+            last_scene: None,
+            full_redraw: false,
+            glyphs: GlyphCache::default(),
+            sprites,
+            notices: Vec::new(),
+        };
+        let preferences = or_default(load_ron::<Preferences>(&Preferences::path()), &mut notices);
+        world.notices = notices;
+        if let Some(preferences) = preferences {
+            world.animate = preferences.animate;
+            world.focused_view = preferences.focused_view;
+            // (a user theme may have been taken out since)
+            world.theme = world
+                .themes
+                .iter()
+                .position(|theme| theme.name == preferences.theme)
+                .unwrap_or(0);
+        }
+        world
+    }
+
+    /// remember how the game looks for next time
+    fn save_preferences(&mut self) {
+        let preferences = Preferences {
+            theme: self.theme().name.clone(),
+            animate: self.animate,
+            focused_view: self.focused_view,
+        };
+        if let Err(e) = save_ron(&Preferences::path(), &preferences) {
+            self.notify(e);
+        }
+    }
+
+    /// tell the player something went wrong, like a file that couldn't be saved. it's shown
+    /// at the bottom of the screen until they next press a key
+    pub fn notify(&mut self, notice: String) {
+        self.notices.push(notice);
+    }
+
+    /// the command to start a bot with, which adds a game against it to the start screen
+    pub fn set_bot_command(&mut self, command: Option<String>) {
+        self.bot_command = command;
+    }
+
+    /// lay the screen out again, after the window changed size or dpi
+    pub fn resize(&mut self, layout: Layout) {
+        self.layout = layout;
+        // the frame buffer was resized, and lost what was drawn in it
+        self.last_scene = None;
+        // and the text is a different size now
+        self.glyphs.clear();
+    }
+
+    /// save the game in progress, so it can be continued next time.
+    /// anything but a game against the computer or a hotseat game is left alone
+    pub fn save_game(&mut self) -> Result<(), String> {
+        match self.status {
+            GameStatus::Playing(GameType::Ai) | GameStatus::Playing(GameType::Hotseat) => {}
+            GameStatus::Playing(GameType::LocalNetwork) => {
+                return Err("network games can't be saved".to_string());
+            }
+            GameStatus::Playing(GameType::ExternalBot) => {
+                return Err("games against a bot can't be saved".to_string());
+            }
+            _ => return Ok(()),
+        }
+        let seed = self.rng.gen();
+        self.rng = StdRng::seed_from_u64(seed);
+        let saved = SavedGame {
+            status: self.status.clone(),
+            this_player: self.this_player.clone(),
+            other_player: self.other_player.clone(),
+            rules: self.rules,
+            player_number: self.player_number,
+            seed,
+            recording: self.recording.clone(),
+            strategy: Some(self.strategy.name().to_string()),
+        };
+        saved.save()
+    }
+
+    /// pick up a saved game where it was left off
+    fn resume(&mut self, saved: SavedGame) {
+        self.status = saved.status;
+        self.this_player = saved.this_player;
+        self.other_player = saved.other_player;
+        self.rules = saved.rules;
+        self.player_number = saved.player_number;
+        self.rng = StdRng::seed_from_u64(saved.seed);
+        self.recording = saved.recording;
+        if let Some(strategy) = saved.strategy.as_deref().and_then(by_name) {
+            self.strategy = strategy;
+        }
+        // whoever has the device now may not be whoever had it before
+        self.privacy_screen = self.status == GameStatus::Playing(GameType::Hotseat);
+    }
+
+    /// whether a network game has been picked, and is still to connect to the other player
+    pub fn needs_connection(&self) -> bool {
+        self.status == GameStatus::Playing(GameType::LocalNetwork) && self.stream.is_none()
+    }
+
+    /// connect to the other player, or wait for them to connect, and agree on the rules.
+    /// `update` does this itself, but it blocks until they do, so a frontend that can't be
+    /// stopped while it blocks can do it first, its own way. if it fails, it's back to the
+    /// start screen to say why
+    pub fn connect(&mut self) {
+        let agreed = try_connect().and_then(|stream| {
+            self.stream = Some(stream);
+            self.agree_on_rules(self.rules)
+        });
+        match agreed {
+            Ok(rules) => self.rules = rules,
+            Err(e) => {
+                self.restart();
+                self.settings.as_mut().unwrap().error = Some(format!("could not connect, {}", e));
+            }
         }
     }
 
     /// Update the `World` internal state
-    pub fn update(&mut self, input: &WinitInputHelper) {
+    ///
+    /// `mouse` is the mouse position in frame pixels, if it's over the frame
+    pub fn update(&mut self, input: &dyn Input, mouse: Option<(usize, usize)>, now: Instant) {
         use GameStatus::*;
-        let actions = self.get_input_actions(input);
+        self.now = now;
+        if self.needs_connection() {
+            self.connect();
+        }
+        // input is locked until the last shots have played out
+        if !self.animations.is_empty() {
+            self.animations.retain(|animation| !animation.is_done(now));
+            if self.animations.is_empty() {
+                self.resolve_shots();
+            }
+            return;
+        }
+
+        let mut actions = self.get_input_actions(input);
+        actions.extend(self.get_mouse_actions(input, mouse));
+        if !actions.is_empty() {
+            self.notices.clear();
+        }
+        if self.is_typing_allowed() {
+            self.type_cell(input, &mut actions);
+        }
+        // peeking only changes what's on screen, so nothing else sees it
+        if actions.contains(&Action::Peek) {
+            self.peek = !self.peek;
+            actions.retain(|action| *action != Action::Peek);
+        }
         match self.status {
             Starting => {
                 self.select_game_type(&actions);
             }
+            KeyBindings => {
+                self.edit_key_bindings(input, &actions);
+            }
+            Playing(GameType::Hotseat) if self.privacy_screen => {
+                if actions.contains(&Action::Enter) {
+                    self.privacy_screen = false;
+                }
+            }
             Playing(_) => {
                 use PlayerStatus::*;
+                let shots = (
+                    self.this_player.shots_taken.len(),
+                    self.other_player.shots_taken.len(),
+                );
                 match self.this_player.status {
                     Placing => self.place_ships(&actions),
                     Aiming => {
                         World::aim(&actions, &mut self.this_player, &mut self.other_player);
+                        self.record(self.player_number, &actions);
                         self.broadcast_actions(&actions);
                     }
                     Waiting => {
@@ -427,447 +1142,1668 @@ impl World<'_> {
                             &mut self.other_player,
                             &mut self.this_player,
                         );
+                        self.record(3 - self.player_number, &other_actions);
                     }
                 }
-                self.check_victory_condition();
+                if self.status == Playing(GameType::ExternalBot) {
+                    self.tell_bot_shots(shots);
+                }
+                if self.animate {
+                    self.animate_shots(shots);
+                }
+                if self.animations.is_empty() {
+                    self.resolve_shots();
+                }
             }
             End(_) => {
-                self.wait_for_restart(&actions);
+                // (after a frame saying so has been drawn)
+                if self
+                    .end_screen
+                    .as_ref()
+                    .is_some_and(|e| e.waiting_for_rematch)
+                {
+                    self.await_rematch();
+                } else {
+                    self.choose_after_game(&actions);
+                }
+            }
+            Replay => {
+                self.watch_replay(&actions);
+            }
+            Stats => {
+                if actions.contains(&Action::Enter) || actions.contains(&Action::Space) {
+                    self.status = Starting;
+                }
             }
         }
     }
 
-    fn get_input_actions(&self, input: &WinitInputHelper) -> Vec<Action> {
-        use Action::*;
-        let mut actions = vec![];
-        if input.key_pressed(VirtualKeyCode::Return) {
-            actions.push(Enter);
-        }
-        if input.key_pressed(VirtualKeyCode::Space) {
-            actions.push(Space);
-        }
-        if input.key_pressed(VirtualKeyCode::Up) {
-            actions.push(Up);
-        }
-        if input.key_pressed(VirtualKeyCode::Down) {
-            actions.push(Down);
-        }
-        if input.key_pressed(VirtualKeyCode::Right) {
-            actions.push(Right);
-        }
-        if input.key_pressed(VirtualKeyCode::Left) {
-            actions.push(Left);
-        }
-        actions
+    fn get_input_actions(&self, input: &dyn Input) -> Vec<Action> {
+        self.bindings
+            .keys
+            .iter()
+            .filter(|(&key, _)| input.key_pressed(key))
+            .map(|(_, action)| action.clone())
+            .collect()
     }
 
-    fn get_other_actions(&mut self) -> Vec<Action> {
-        use GameStatus::*;
-        use GameType::*;
-
-        match self.status {
-            Starting | End(_) => { unreachable!("should not be reading the other players actions unless we are Playing") }
-            Playing(Ai) => self.gen_ai_actions(),
-            Playing(LocalNetwork) => self.receive_broadcast_actions()
-        }
+    fn is_typing_allowed(&self) -> bool {
+        matches!(self.status, GameStatus::Playing(_))
+            && !self.privacy_screen
+            && self.this_player.status == PlayerStatus::Aiming
     }
 
-    fn broadcast_ship_positions(&mut self) {
-        if self.stream.is_some() {
-            let message = ron::ser::to_string(&self.this_player.ships).unwrap();
-            match self.stream.as_mut().unwrap().send_message(&message) {
-                Ok(_) => {}
-                Err(e) => panic!("could not send message to connection, {}", e),
-            };
-        }
+    /// whether there's a half-typed cell that escape should cancel, rather than quitting
+    pub fn is_typing(&self) -> bool {
+        self.is_typing_allowed() && !self.typed_cell.is_empty()
     }
 
-    fn receive_ship_positions(&mut self) -> Vec<Ship> {
-        if self.stream.is_some() {
-            let actions_string = self.stream.as_mut().unwrap().read_message().unwrap();
-            ron::de::from_str(&actions_string).unwrap()
-        } else {
-            panic!("no connection, cannot read ship positions.");
+    /// typing a cell like "B7" and pressing enter fires straight at it
+    ///
+    /// a row letter starts a cell, and once one is started, letters, numbers and backspace all
+    /// go to it. keys bound to actions don't also do their action while they're being typed
+    fn type_cell(&mut self, input: &dyn Input, actions: &mut Vec<Action>) {
+        let mut typed = false;
+        for text_char in input.text() {
+            match text_char {
+                TextChar::Char(c) if self.typed_cell.is_empty() => {
+                    if let 'A'..='J' = c.to_ascii_uppercase() {
+                        self.typed_cell.push(c.to_ascii_uppercase());
+                        self.typed_cell_error = None;
+                        typed = true;
+                    }
+                }
+                TextChar::Char(c) if c.is_ascii_alphanumeric() => {
+                    if self.typed_cell.len() < 3 {
+                        self.typed_cell.push(c.to_ascii_uppercase());
+                        self.typed_cell_error = None;
+                    }
+                    typed = true;
+                }
+                TextChar::Back if !self.typed_cell.is_empty() => {
+                    self.typed_cell.pop();
+                    self.typed_cell_error = None;
+                    typed = true;
+                }
+                _ => {}
+            }
+        }
+        if typed {
+            let typed_actions: Vec<Action> = self
+                .bindings
+                .keys
+                .iter()
+                .filter(|(&key, _)| is_text_key(key) && input.key_pressed(key))
+                .map(|(_, action)| action.clone())
+                .collect();
+            actions.retain(|action| !typed_actions.contains(action));
         }
-    }
 
-    fn receive_broadcast_actions(&mut self) -> Vec<Action> {
-        if self.stream.is_some() {
-            let actions_string = self.stream.as_mut().unwrap().read_message().unwrap();
-            ron::de::from_str(&actions_string).unwrap()
-        } else {
-            panic!("no connection, cannot get the other player's moves.")
+        if input.key_pressed(VirtualKeyCode::Escape) {
+            self.typed_cell.clear();
+            self.typed_cell_error = None;
         }
-    }
 
-    fn broadcast_actions(&mut self, actions: &[Action]) {
-        if self.stream.is_some() && !actions.is_empty() {
-            let message = ron::ser::to_string(&actions).unwrap();
-            match self.stream.as_mut().unwrap().send_message(&message) {
-                Ok(_) => {}
-                Err(e) => panic!("could not send message to connection, {}", e),
+        let submitted = actions
+            .iter()
+            .any(|action| *action == Action::Enter || *action == Action::Space);
+        if submitted && !self.typed_cell.is_empty() {
+            actions.retain(|action| *action != Action::Enter && *action != Action::Space);
+            match Cell::parse(&self.typed_cell) {
+                Ok(cell) if self.this_player.shots_taken.contains(&cell) => {
+                    self.typed_cell_error = Some(format!("already fired at {}", self.typed_cell));
+                }
+                Ok(cell) => {
+                    actions.push(Action::FireAt(cell));
+                    self.typed_cell.clear();
+                }
+                Err(e) => self.typed_cell_error = Some(e),
             }
         }
     }
 
-    fn gen_ai_actions(&mut self) -> Vec<Action> {
-        // move or shoot with some %
-        // on average, move 10 times for every shot
-        let shoot: f64 = self.rng.gen();
-        let action = match shoot {
-            x if x > 0.1 => {
-                let direction: Direction = self.rng.gen();
-                direction.into()
+    /// translate clicks, drags and the scroll wheel into the same actions the keyboard makes
+    fn get_mouse_actions(
+        &mut self,
+        input: &dyn Input,
+        mouse: Option<(usize, usize)>,
+    ) -> Vec<Action> {
+        use GameStatus::*;
+        let clicked = input.mouse_pressed(0);
+        let mut actions = vec![];
+        match &self.status {
+            Starting => {
+                let item = mouse.and_then(|(x, y)| self.menu_item_at(x, y));
+                if let (true, Some(item), Some(settings)) = (clicked, item, &self.settings) {
+                    let len = self.menu().len();
+                    let steps = (item + len - settings.selected) % len;
+                    actions.extend(repeat_n(Action::Down, steps));
+                    actions.push(Action::Enter);
+                }
             }
-            _ => Action::Enter,
-        };
-        vec![action]
-    }
+            Playing(_) if self.privacy_screen => {
+                if clicked {
+                    actions.push(Action::Enter);
+                }
+            }
+            Playing(_) => {
+                let cell = mouse.and_then(|(x, y)| self.layout.cell_at(x, y));
+                match self.this_player.status {
+                    PlayerStatus::Placing => {
+                        let ship = self.this_player.ship_to_place();
+                        match (cell, &self.drag) {
+                            // pick up the ship being placed
+                            (Some((cell, true)), None)
+                                if clicked && ship.is_some_and(|s| s.cells.contains(&cell)) =>
+                            {
+                                self.drag = Some(cell);
+                            }
+                            // drop it where it is
+                            (_, Some(_)) if input.mouse_released(0) => {
+                                self.drag = None;
+                                actions.push(Action::Enter);
+                            }
+                            // move it along with the mouse
+                            (Some((cell, true)), Some(from)) => {
+                                actions.extend(
+                                    from.directions_to(&cell).into_iter().map(Action::from),
+                                );
+                                self.drag = Some(cell);
+                            }
+                            _ => {}
+                        }
+                        if input.mouse_pressed(1) || input.scroll_diff() != 0.0 {
+                            actions.push(Action::Space);
+                        }
+                    }
+                    PlayerStatus::Aiming => {
+                        if let (true, Some((cell, false))) = (clicked, cell) {
+                            actions.push(Action::FireAt(cell));
+                        }
+                    }
+                    PlayerStatus::Waiting => {}
+                }
+            }
+            KeyBindings | Replay => {}
+            Stats => {
+                if clicked {
+                    actions.push(Action::Enter);
+                }
+            }
+            End(_) => {
+                let choice = mouse.and_then(|(x, y)| self.end_choice_at(x, y));
+                if let (true, Some(choice), Some(end_screen)) = (clicked, choice, &self.end_screen)
+                {
+                    let len = END_CHOICES.len();
+                    let steps = (choice + len - end_screen.selected) % len;
+                    actions.extend(repeat_n(Action::Right, steps));
+                    actions.push(Action::Enter);
+                }
+            }
+        }
+        actions
+    }
+
+    /// the entries on the start screen: `MENU`, after 'continue' if there's a saved game
+    fn menu(&self) -> Vec<MenuItem> {
+        let saved = self
+            .saved_game
+            .as_ref()
+            .and_then(|saved| match saved.status {
+                GameStatus::Playing(game_type) => Some(MenuItem::Continue(game_type)),
+                _ => None,
+            });
+        // there's only a bot to play against if one was given
+        let bot = self.bot_command.is_some();
+        saved
+            .into_iter()
+            .chain(MENU.iter().cloned())
+            .filter(|item| bot || *item != MenuItem::Start(GameType::ExternalBot))
+            .collect()
+    }
+
+    /// index into `World::menu` of the entry drawn at this pixel, if any
+    fn menu_item_at(&self, x: usize, y: usize) -> Option<usize> {
+        let (x, y) = self.layout.design_point((x, y));
+        if x < GRID_MARGIN || y < MENU_TOP {
+            return None;
+        }
+        Some(((y - MENU_TOP) / MENU_ROW_HEIGHT) as usize).filter(|&i| i < self.menu().len())
+    }
+
+    /// index into `END_CHOICES` of the choice drawn at this pixel, if any
+    fn end_choice_at(&self, x: usize, y: usize) -> Option<usize> {
+        let (x, y) = self.layout.design_point((x, y));
+        let left = CENTER_X - END_CHOICE_WIDTH * END_CHOICES.len() as f32 / 2.0;
+        if x < left || !(END_CHOICES_TOP..=END_CHOICES_TOP + 30.0).contains(&y) {
+            return None;
+        }
+        Some(((x - left) / END_CHOICE_WIDTH) as usize).filter(|&i| i < END_CHOICES.len())
+    }
+
+    fn get_other_actions(&mut self) -> Vec<Action> {
+        use GameStatus::*;
+        use GameType::*;
+
+        match self.status {
+            Starting | KeyBindings | End(_) | Replay | Stats => {
+                unreachable!(
+                    "should not be reading the other players actions unless we are Playing"
+                )
+            }
+            Playing(Ai) => self.gen_ai_actions(),
+            Playing(LocalNetwork) => self.receive_broadcast_actions(),
+            Playing(ExternalBot) if self.other_player.status == PlayerStatus::Placing => {
+                self.place_bot_fleet();
+                Vec::new()
+            }
+            Playing(ExternalBot) => self.receive_bot_shot(),
+            Playing(Hotseat) => {
+                unreachable!("in hotseat games, the other player takes their turn on this device")
+            }
+        }
+    }
+
+    /// end the game, or hand the device over in hotseat games, once the last shot has landed
+    fn resolve_shots(&mut self) {
+        let game_type = match self.status {
+            GameStatus::Playing(game_type) => game_type,
+            _ => return,
+        };
+        self.check_victory_condition();
+        if let GameStatus::End(result) = self.status.clone() {
+            if game_type == GameType::ExternalBot {
+                let bot_won = result == GameResult::Defeat;
+                self.send_to_bot(&ToBot::GameOver { bot_won });
+            }
+            self.end_game(game_type, result, None);
+        }
+        let turn_over = self.this_player.status == PlayerStatus::Waiting;
+        if self.status == GameStatus::Playing(GameType::Hotseat) && turn_over {
+            self.pass_device();
+        }
+    }
+
+    /// add actions aimed at the other fleet to the recording, for the player (1 or 2) who took them
+    fn record(&mut self, player_number: u8, actions: &[Action]) {
+        if let Some(recording) = self.recording.as_mut().filter(|_| !actions.is_empty()) {
+            recording.moves.push((player_number, actions.to_vec()));
+        }
+    }
+
+    /// write the finished game's replay to a file of its own, with both fleets as they were
+    /// placed, and say where
+    fn save_recording(&mut self) -> Option<PathBuf> {
+        let players = [&self.this_player, &self.other_player];
+        // `this_player` is whoever has the device, which is player 2 half the time in hotseat
+        let first = (self.player_number - 1) as usize;
+        if let Some(recording) = self.recording.as_mut() {
+            recording.fleets = [
+                players[first].ships.clone(),
+                players[1 - first].ships.clone(),
+            ];
+            let path = Replay::new_path();
+            return match recording.save(&path) {
+                Ok(()) => Some(path),
+                Err(e) => {
+                    self.notify(e);
+                    None
+                }
+            };
+        }
+        None
+    }
+
+    /// count the finished game in the stats, from this player's side, or player 1's in hotseat
+    fn record_stats(&mut self, game_type: GameType, result: &GameResult) {
+        let (player, other_player) = match self.player_number {
+            1 => (&self.this_player, &self.other_player),
+            _ => (&self.other_player, &self.this_player),
+        };
+        let hits = player
+            .shots_taken
+            .iter()
+            .filter(|shot| {
+                other_player
+                    .ships
+                    .iter()
+                    .any(|ship| ship.cells.contains(shot))
+            })
+            .count();
+        let won = matches!(result, GameResult::Victory | GameResult::PlayerWon(1));
+        let shots = player.shots_taken.len() as u32;
+        self.stats.record(game_type, won, shots, hits as u32);
+        if let Err(e) = self.stats.save() {
+            self.notify(e);
+        }
+    }
+
+    /// start animating the shots taken since each player had taken some number of them
+    fn animate_shots(&mut self, (this_shots, other_shots): (usize, usize)) {
+        let now = self.now;
+        let new_shots = [
+            (
+                &self.this_player.shots_taken[this_shots..],
+                &self.this_player,
+                &self.other_player,
+                false,
+            ),
+            (
+                &self.other_player.shots_taken[other_shots..],
+                &self.other_player,
+                &self.this_player,
+                true,
+            ),
+        ];
+        for (shots, shooter, target, this_player) in new_shots.iter() {
+            for shot in shots.iter() {
+                let hit_ship = target.ships.iter().find(|ship| ship.cells.contains(shot));
+                let effect = match hit_ship {
+                    Some(_) => Effect::Explosion,
+                    None => Effect::Ripple,
+                };
+                let animation = Animation::new(effect, vec![shot.clone()], *this_player, now);
+                // a ship that just sank burns once the explosion is over
+                if let Some(ship) =
+                    hit_ship.filter(|ship| World::is_sunk(ship, &shooter.shots_taken))
+                {
+                    let start = animation.end();
+                    self.animations.push(Animation::new(
+                        Effect::Sinking,
+                        ship.cells.clone(),
+                        *this_player,
+                        start,
+                    ));
+                }
+                self.animations.push(animation);
+            }
+        }
+    }
+
+    /// hotseat: swap seats, and hide the boards until the next player is ready
+    fn pass_device(&mut self) {
+        std::mem::swap(&mut self.this_player, &mut self.other_player);
+        self.player_number = 3 - self.player_number;
+        self.privacy_screen = true;
+    }
+
+    fn broadcast_ship_positions(&mut self) {
+        if let Some(stream) = self.stream.as_mut() {
+            let message = ron::ser::to_string(&self.this_player.ships).unwrap();
+            match stream.send_message(&message) {
+                Ok(_) => {}
+                Err(e) => panic!("could not send message to connection, {}", e),
+            };
+        }
+    }
+
+    fn receive_ship_positions(&mut self) -> Vec<Ship> {
+        if let Some(stream) = self.stream.as_mut() {
+            let actions_string = stream.read_message().unwrap();
+            ron::de::from_str(&actions_string).unwrap()
+        } else {
+            panic!("no connection, cannot read ship positions.");
+        }
+    }
+
+    fn receive_broadcast_actions(&mut self) -> Vec<Action> {
+        if let Some(stream) = self.stream.as_mut() {
+            let actions_string = stream.read_message().unwrap();
+            ron::de::from_str(&actions_string).unwrap()
+        } else {
+            panic!("no connection, cannot get the other player's moves.")
+        }
+    }
+
+    fn broadcast_actions(&mut self, actions: &[Action]) {
+        if let Some(stream) = self.stream.as_mut().filter(|_| !actions.is_empty()) {
+            let message = ron::ser::to_string(&actions).unwrap();
+            match stream.send_message(&message) {
+                Ok(_) => {}
+                Err(e) => panic!("could not send message to connection, {}", e),
+            }
+        }
+    }
+
+    /// the game is over: forget any save, record it, and show how it went
+    fn end_game(&mut self, game_type: GameType, result: GameResult, forfeit: Option<String>) {
+        self.status = GameStatus::End(result.clone());
+        // a finished game can't be continued
+        SavedGame::delete();
+        let replay = self.save_recording();
+        self.record_stats(game_type, &result);
+        self.end_screen = Some(EndScreen {
+            game_type,
+            selected: 0,
+            replay,
+            waiting_for_rematch: false,
+            other_left: false,
+            forfeit,
+        });
+    }
+
+    /// the bot broke the protocol, hung or exited, so it loses
+    fn bot_forfeits(&mut self, error: String) {
+        // and it's done with, so it's stopped now rather than on leaving the end screen
+        self.stream = None;
+        self.end_game(GameType::ExternalBot, GameResult::Victory, Some(error));
+    }
+
+    fn send_to_bot(&mut self, message: &ToBot) {
+        if let Some(stream) = self.stream.as_mut() {
+            let message = ron::ser::to_string(message).unwrap();
+            // a bot that's gone is found out when it doesn't answer
+            let _ = stream.send_message(&message);
+        }
+    }
+
+    /// send the bot something it has to answer, and start the clock on it
+    fn ask_bot(&mut self, message: &ToBot) {
+        self.send_to_bot(message);
+        self.bot_asked = Some(self.now);
+    }
+
+    /// the bot's answer, if it has come in yet. an error if it isn't a message, the bot has
+    /// exited, or it has taken too long
+    fn receive_from_bot(&mut self) -> Result<Option<FromBot>, String> {
+        let stream = match self.stream.as_mut() {
+            Some(stream) => stream,
+            None => return Err("the bot isn't running".to_string()),
+        };
+        let line = stream
+            .try_read_message()
+            .map_err(|e| format!("could not hear from the bot, {}", e))?;
+        let line = match line {
+            Some(line) => line,
+            None => {
+                let asked = self.bot_asked.unwrap_or(self.now);
+                if self.now.duration_since(asked) > BOT_TIMEOUT {
+                    return Err(format!("no answer in {} seconds", BOT_TIMEOUT.as_secs()));
+                }
+                return Ok(None);
+            }
+        };
+        self.bot_asked = None;
+        ron::de::from_str(&line)
+            .map(Some)
+            .map_err(|e| format!("the bot sent {:?}, which isn't a message, {}", line, e))
+    }
+
+    /// the bot's fleet, once it has sent it, checked to be one that could have been placed by
+    /// hand
+    fn receive_bot_fleet(&mut self) -> Result<Option<Vec<Ship>>, String> {
+        let fleet = match self.receive_from_bot()? {
+            Some(FromBot::Fleet(fleet)) => fleet,
+            Some(message) => {
+                return Err(format!("the bot sent {:?} instead of its fleet", message))
+            }
+            None => return Ok(None),
+        };
+        Ship::fleet(fleet, &SHIP_LENGTHS, &self.rules)
+            .map(Some)
+            .map_err(|e| format!("the bot's fleet can't be placed, {}", e))
+    }
+
+    /// start firing once the bot's fleet is in
+    fn place_bot_fleet(&mut self) {
+        match self.receive_bot_fleet() {
+            Ok(Some(ships)) => {
+                self.other_player.ships = ships;
+                self.other_player.status = PlayerStatus::Waiting;
+                self.this_player.status = PlayerStatus::Aiming;
+            }
+            Ok(None) => {}
+            Err(e) => self.bot_forfeits(e),
+        }
+    }
+
+    /// where the bot fires next, once it has picked. nothing until then, or if it forfeits
+    fn receive_bot_shot(&mut self) -> Vec<Action> {
+        if self.bot_asked.is_none() {
+            self.ask_bot(&ToBot::YourTurn);
+        }
+        match self.receive_from_bot() {
+            Ok(Some(FromBot::Fire(cell)))
+                if (cell.x as usize) < CELL_COUNT && (cell.y as usize) < CELL_COUNT =>
+            {
+                vec![Action::FireAt(cell)]
+            }
+            Ok(Some(message)) => {
+                self.bot_forfeits(format!(
+                    "the bot sent {:?} instead of a cell on the grid to fire at",
+                    message
+                ));
+                Vec::new()
+            }
+            Ok(None) => Vec::new(),
+            Err(e) => {
+                self.bot_forfeits(e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// start the program given with `--bot`
+    fn start_bot(&self) -> Result<LinesCodec, String> {
+        let command = self.bot_command.as_deref().unwrap_or_default();
+        LinesCodec::spawn(command)
+            .map_err(|e| format!("could not start the bot {:?}, {}", command, e))
+    }
+
+    /// tell the bot where the shots taken since each player had taken some number of them landed
+    fn tell_bot_shots(&mut self, (this_shots, other_shots): (usize, usize)) {
+        let mut messages = Vec::new();
+        let new_shots = [
+            (this_shots, &self.this_player, &self.other_player, false),
+            (other_shots, &self.other_player, &self.this_player, true),
+        ];
+        for (shots, shooter, target, by_bot) in new_shots.iter() {
+            for cell in shooter.shots_taken[*shots..].iter() {
+                let hit_ship = target.ships.iter().find(|ship| ship.cells.contains(cell));
+                messages.push(ToBot::ShotResult {
+                    by_bot: *by_bot,
+                    cell: cell.clone(),
+                    hit: hit_ship.is_some(),
+                    sunk: hit_ship.is_some_and(|ship| World::is_sunk(ship, &shooter.shots_taken)),
+                });
+            }
+        }
+        for message in messages.iter() {
+            self.send_to_bot(message);
+        }
+    }
+
+    /// the computer's next move: it walks the target over to where its strategy fires next,
+    /// a step at a time, so it can be seen aiming
+    fn gen_ai_actions(&mut self) -> Vec<Action> {
+        if self.ai_moves.is_empty() {
+            let board = World::board(&self.other_player, &self.this_player, &self.rules);
+            let cell = self.strategy.next_shot(&board, &mut self.rng);
+            let walk = self.other_player.target.directions_to(&cell);
+            // (in reverse, to pop them off the end)
+            self.ai_moves = Some(Action::FireAt(cell))
+                .into_iter()
+                .chain(walk.into_iter().rev().map(Action::from))
+                .collect();
+        }
+        self.ai_moves.pop().into_iter().collect()
+    }
+
+    /// what `player` can see of `other_player`'s fleet, for a computer player to aim with
+    fn board(player: &Player, other_player: &Player, rules: &Rules) -> Board {
+        let hit = |shot: &Cell| {
+            other_player
+                .ships
+                .iter()
+                .any(|ship| ship.cells.contains(shot))
+        };
+        let (sunk, afloat): (Vec<&Ship>, Vec<&Ship>) = other_player
+            .ships
+            .iter()
+            .partition(|ship| World::is_sunk(ship, &player.shots_taken));
+        Board {
+            target: player.target.clone(),
+            shots: player
+                .shots_taken
+                .iter()
+                .map(|shot| (shot.clone(), hit(shot)))
+                .collect(),
+            sunk: sunk.iter().map(|ship| ship.cells.clone()).collect(),
+            afloat: afloat.iter().map(|ship| ship.len).collect(),
+            no_touching: rules.no_touching,
+        }
+    }
+
+    /// a whole game between two computer players, with no window, by the same rules as on
+    /// screen. `first` (0 or 1) fires first. returns the winner, and the shots they won in,
+    /// or why a fleet couldn't be placed
+    pub(crate) fn play_computer_game(
+        rng: &mut StdRng,
+        rules: &Rules,
+        strategies: &mut [Box<dyn Strategy>; 2],
+        first: usize,
+    ) -> Result<(usize, usize), String> {
+        let mut players = [Player::new(), Player::new()];
+        for (player, strategy) in players.iter_mut().zip(strategies.iter_mut()) {
+            let fleet = strategy.place_fleet(&SHIP_LENGTHS, rules.no_touching, rng)?;
+            player.ships = match Ship::fleet(fleet, &SHIP_LENGTHS, rules) {
+                Ok(ships) => ships,
+                Err(e) => panic!("a computer player's fleet can't be placed, {}", e),
+            };
+            player.status = PlayerStatus::Waiting;
+        }
+        players[first].status = PlayerStatus::Aiming;
+        let mut turn = first;
+        loop {
+            let [one, two] = &mut players;
+            let (player, other_player) = if turn == 0 { (one, two) } else { (two, one) };
+            let board = World::board(player, other_player, rules);
+            let cell = strategies[turn].next_shot(&board, rng);
+            World::aim(&[Action::FireAt(cell)], player, other_player);
+            if player.status == PlayerStatus::Waiting {
+                let won = other_player
+                    .ships
+                    .iter()
+                    .all(|ship| World::is_sunk(ship, &player.shots_taken));
+                if won {
+                    return Ok((turn, player.shots_taken.len()));
+                }
+                turn = 1 - turn;
+            }
+        }
+    }
+
+    /// the host's rules win, so that both sides play the same game
+    fn agree_on_rules(&mut self, rules: Rules) -> io::Result<Rules> {
+        let stream = match self.stream.as_mut() {
+            Some(stream) => stream,
+            None => panic!("no connection, cannot agree on rules."),
+        };
+        if stream.host {
+            let message = ron::ser::to_string(&rules).unwrap();
+            stream.send_message(&message)?;
+            Ok(rules)
+        } else {
+            let rules_string = stream.read_message()?;
+            let host_rules: Rules = ron::de::from_str(&rules_string)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            if host_rules != rules {
+                self.notify(format!("playing with the host's rules: {:?}", host_rules));
+            }
+            Ok(host_rules)
+        }
+    }
 
     fn begin_game(&mut self) {
         match self.status {
             GameStatus::Playing(GameType::Ai) => {
-                self.other_player.ships = Ship::random_five(&mut self.rng);
+                // before this fleet is counted, or it would know where to look
+                self.strategy.study(&self.stats.fleets);
+                let placed =
+                    self.strategy
+                        .place_fleet(&SHIP_LENGTHS, self.rules.no_touching, &mut self.rng);
+                let fleet = match placed {
+                    Ok(fleet) => fleet,
+                    Err(e) => {
+                        self.restart();
+                        self.settings.as_mut().unwrap().error = Some(e);
+                        return;
+                    }
+                };
+                self.other_player.ships = match Ship::fleet(fleet, &SHIP_LENGTHS, &self.rules) {
+                    Ok(ships) => ships,
+                    Err(e) => panic!("the computer's fleet can't be placed, {}", e),
+                };
                 self.other_player.status = PlayerStatus::Waiting;
                 self.this_player.status = PlayerStatus::Aiming;
             }
+            GameStatus::Playing(GameType::ExternalBot) => {
+                // firing starts once the bot's fleet is in
+                self.ask_bot(&ToBot::PlaceFleet {
+                    lengths: SHIP_LENGTHS.to_vec(),
+                    no_touching: self.rules.no_touching,
+                });
+                self.this_player.status = PlayerStatus::Waiting;
+            }
             GameStatus::Playing(GameType::LocalNetwork) => {
                 self.broadcast_ship_positions();
                 self.other_player.ships = self.receive_ship_positions();
                 self.other_player.status = PlayerStatus::Waiting;
                 self.this_player.status = PlayerStatus::Aiming;
             }
+            GameStatus::Playing(GameType::Hotseat) => {
+                // either the second player still has to place their ships,
+                // or both fleets are out and the first player fires first
+                self.this_player.status = PlayerStatus::Waiting;
+                if self.other_player.status != PlayerStatus::Placing {
+                    self.other_player.status = PlayerStatus::Aiming;
+                }
+            }
             _ => {}
         }
+        // in hotseat, neither fleet is the player on this side's alone
+        if let GameStatus::Playing(game_type) = self.status {
+            if game_type != GameType::Hotseat {
+                let cells = self.this_player.ships.iter().flat_map(|ship| &ship.cells);
+                self.stats.record_fleet(cells);
+                if let Err(e) = self.stats.save() {
+                    self.notify(e);
+                }
+            }
+        }
     }
 
-    fn clear_top(frame: &mut [u8]) {
-        //
-        // top frame pixels
-        //
-        for i in 0..TOP_MARGIN {
-            let i = i as usize;
-            let w = WIDTH as usize;
-            let pixels = BACKGROUND
-                .iter()
-                .cycle()
-                .cloned()
-                .take(w * 4)
-                .collect::<Vec<_>>();
-            frame[i * w * 4..(i + 1) * w * 4].copy_from_slice(&pixels);
+    /// draw a whole scene onto a blank frame
+    fn draw_scene(&self, frame: &mut [u8], scene: &Scene) {
+        self.clear(frame);
+        if scene.grids {
+            self.clear_grids(frame);
+        }
+        for (cell, this_player, shape, color) in &scene.cells {
+            self.paint_cell(frame, cell, *shape, *color, *this_player);
+        }
+        for text in &scene.texts {
+            self.paint_text(frame, text);
         }
     }
 
-    fn clear_grids(frame: &mut [u8]) {
-        //
-        // draw two grids
-        //
-        for line in 0..GRID_WIDTH {
-            let i = (line + TOP_MARGIN) as usize;
-            let w = WIDTH as usize;
-            let grid_pixels: Vec<u8> = if line % CELL_WIDTH == 0 {
-                repeat(GRID_LINES).take(GRID_WIDTH).flatten().collect()
-            } else {
-                // empty
-                once(GRID_LINES)
-                    .chain(repeat(GRID_EMPTY).take(29))
-                    .cycle()
-                    .take(GRID_WIDTH)
-                    .flatten()
-                    .collect()
-            };
+    /// draw over just the cells and text that changed since the last scene
+    fn draw_changes(&self, frame: &mut [u8], last: &Scene, scene: &Scene) {
+        let changed_cells = scene.changed_cells(last);
+        let mut dirty: Vec<Rect> = scene
+            .new_texts(last)
+            .chain(last.new_texts(scene))
+            .filter_map(|text| self.text_rect(text))
+            .collect();
+        if changed_cells.is_empty() && dirty.is_empty() {
+            return;
+        }
 
-            let margin: Vec<u8> = repeat(BACKGROUND).take(GRID_MARGIN).flatten().collect();
-            let pixels: Vec<u8> = margin
-                .iter()
-                .chain(grid_pixels.iter())
-                .chain(margin.iter())
-                .chain(grid_pixels.iter())
-                .chain(margin.iter())
-                .cloned()
-                .take(w * 4)
-                .collect();
+        // text over the grids would have to be redrawn along with the cells under it,
+        // so just start over
+        let texts: Vec<(&Text, Rect)> = scene
+            .texts
+            .iter()
+            .filter_map(|text| self.text_rect(text).map(|rect| (text, rect)))
+            .collect();
+        let grids = [self.layout.grid(true), self.layout.grid(false)];
+        let over_grids = texts
+            .iter()
+            .map(|(_, rect)| rect)
+            .chain(dirty.iter())
+            .any(|rect| grids.iter().any(|grid| rect.intersects(grid)));
+        if scene.grids && over_grids {
+            return self.draw_scene(frame, scene);
+        }
+
+        for (cell, this_player) in changed_cells {
+            self.fill_rect(
+                frame,
+                self.layout.cell(&cell, this_player),
+                self.theme().grid_empty,
+            );
+            for (shape, color) in scene.fills(&cell, this_player) {
+                self.paint_cell(frame, &cell, shape, color, this_player);
+            }
+        }
 
-            frame[i * w * 4..(i + 1) * w * 4].copy_from_slice(&pixels);
+        // text blends with what's under it, so any text touching a dirty area is cleared
+        // and redrawn whole, which can make more of the frame dirty in turn
+        let mut redraw = vec![false; texts.len()];
+        loop {
+            let mut grew = false;
+            for (i, (_, rect)) in texts.iter().enumerate() {
+                if !redraw[i] && dirty.iter().any(|d| d.intersects(rect)) {
+                    redraw[i] = true;
+                    dirty.push(*rect);
+                    grew = true;
+                }
+            }
+            if !grew {
+                break;
+            }
+        }
+
+        for rect in &dirty {
+            self.fill_rect(frame, *rect, self.theme().background);
+        }
+        for (i, (text, _)) in texts.iter().enumerate() {
+            if redraw[i] {
+                self.paint_text(frame, text);
+            }
         }
     }
 
-    fn clear_bottom(frame: &mut [u8]) {
-        // 150px of empty (dark green)
-        for i in TOP_MARGIN + GRID_WIDTH..HEIGHT as usize {
-            let i = i as usize;
-            let w = WIDTH as usize;
-            let pixels = BACKGROUND
-                .iter()
-                .cycle()
-                .cloned()
-                .take(w * 4)
-                .collect::<Vec<_>>();
-            frame[i * w * 4..(i + 1) * w * 4].copy_from_slice(&pixels);
+    fn theme(&self) -> &Theme {
+        &self.themes[self.theme]
+    }
+
+    /// fill the whole frame with the background color
+    fn clear(&self, frame: &mut [u8]) {
+        let all = Rect {
+            x: 0,
+            y: 0,
+            width: self.layout.width,
+            height: self.layout.height,
+        };
+        self.fill_rect(frame, all, self.theme().background);
+    }
+
+    fn clear_grids(&self, frame: &mut [u8]) {
+        //
+        // draw the grids: grid lines underneath, with empty cells on top
+        //
+        for this_player in self.layout.grids() {
+            self.fill_rect(
+                frame,
+                self.layout.grid(this_player),
+                self.theme().grid_lines,
+            );
+            for y in 0..CELL_COUNT as u8 {
+                for x in 0..CELL_COUNT as u8 {
+                    let cell = self.layout.cell(&Cell { x, y }, this_player);
+                    self.fill_rect(frame, cell, self.theme().grid_empty);
+                }
+            }
         }
     }
 
-    fn draw_ships(&self, frame: &mut [u8]) {
-        for ship in self.this_player.ships.iter() {
+    fn draw_ships(&self, scene: &mut Scene) {
+        self.draw_fleet(scene, &self.this_player.ships, true);
+    }
+
+    /// a fleet on one of the grids, `true` for this player's
+    fn draw_fleet(&self, scene: &mut Scene, ships: &[Ship], this_player: bool) {
+        let theme = self.theme();
+        for ship in ships.iter() {
             use ShipStatus::*;
             let color = match ship.status {
-                Placing => YELLOW,
-                Locked => GREEN,
-                _ => GRID_EMPTY,
+                Placing => theme.ship_placing,
+                Locked => theme.ship_locked,
+                Hidden => continue,
             };
-            if ship.status != Hidden {
-                for cell in &ship.cells {
-                    World::fill_cell(cell, frame, color, true);
-                }
+            for cell in &ship.cells {
+                let shape = match self.sprites {
+                    Some(_) => ship.sprite(cell),
+                    None => ship.hull(cell),
+                };
+                scene.fill_cell(cell, shape, color, this_player);
             }
         }
     }
 
-    fn draw_shots(&self, frame: &mut [u8]) {
-        for shot in self.this_player.shots_taken.iter() {
-            // check if it was a hit by iterating over the cells in the other player's ships
-            let color = match self
-                .other_player
-                .ships
-                .iter()
-                .any(|ship| ship.cells.iter().any(|sc| sc == shot))
-            {
-                true => BLACK,  // hit
-                false => WHITE, // miss
-            };
-            World::fill_cell(shot, frame, color, false);
-        }
+    fn draw_shots(&self, scene: &mut Scene) {
+        let (this_player, other_player) = (&self.this_player, &self.other_player);
+        self.draw_shots_on(scene, &this_player.shots_taken, &other_player.ships, false);
+        self.draw_shots_on(scene, &other_player.shots_taken, &this_player.ships, true);
+    }
 
-        for shot in self.other_player.shots_taken.iter() {
-            // check if it was a hit by iterating over the cells in the other player's ships
-            let color = match self
-                .this_player
-                .ships
+    /// hits and misses on the fleet on one of the grids, `true` for this player's
+    fn draw_shots_on(&self, scene: &mut Scene, shots: &[Cell], ships: &[Ship], this_player: bool) {
+        let theme = self.theme();
+        for shot in shots.iter() {
+            // check if it was a hit by iterating over the cells in the fleet's ships
+            let (shape, color) = match ships
                 .iter()
                 .any(|ship| ship.cells.iter().any(|sc| sc == shot))
             {
-                true => BLACK,  // hit
-                false => WHITE, // miss
+                true => (Shape::Cross, theme.hit), // hit
+                false => (Shape::Dot, theme.miss), // miss
             };
-            World::fill_cell(shot, frame, color, true);
+            scene.fill_cell(shot, shape, color, this_player);
         }
     }
 
-    fn draw_target(&self, frame: &mut [u8]) {
+    fn draw_target(&self, scene: &mut Scene) {
+        let theme = self.theme();
         if self.this_player.status == PlayerStatus::Aiming {
-            World::fill_cell(&self.this_player.target, frame, FLAME, false);
+            let target = &self.this_player.target;
+            scene.fill_cell(target, Shape::Reticle, theme.target, false);
         }
 
         if self.other_player.status == PlayerStatus::Aiming {
-            World::fill_cell(&self.other_player.target, frame, YELLOW, true);
+            let target = &self.other_player.target;
+            scene.fill_cell(target, Shape::Reticle, theme.enemy_target, true);
         }
     }
 
-    fn draw_start_screen(&self, frame: &mut [u8]) {
-        World::draw_text(frame, "Battleship", &self.font, GREEN, 60.0, (20.0, 0.0));
-        if let Some(settings) = &self.settings {
-            let (network_color, ai_color) = match settings.game_type {
-                GameType::LocalNetwork => (YELLOW, GREEN),
-                GameType::Ai => (GREEN, YELLOW),
+    fn draw_animations(&self, scene: &mut Scene) {
+        let theme = self.theme();
+        for animation in &self.animations {
+            let progress = match animation.progress(self.now) {
+                Some(progress) => progress,
+                None => continue,
             };
-            World::draw_text(
-                frame,
-                "start local network game",
-                &self.font,
-                network_color,
-                40.0,
-                (40.0, 60.0),
-            );
-            World::draw_text(
-                frame,
-                "start game vs. computer",
-                &self.font,
-                ai_color,
-                40.0,
-                (40.0, 100.0),
-            );
+            let (shape, color) = match animation.effect {
+                Effect::Ripple => (Shape::Ripple(progress), theme.miss),
+                Effect::Explosion => (Shape::Burst(progress), theme.highlight),
+                Effect::Sinking => (Shape::Flame(progress), theme.error),
+            };
+            // everything fades out towards the end
+            let alpha = (color[3] as f32 * (1.0 - progress * progress)) as u8;
+            let color = [color[0], color[1], color[2], alpha];
+            for cell in &animation.cells {
+                scene.fill_cell(cell, shape, color, animation.this_player);
+            }
+        }
+    }
 
-            let instructions = "up and down to select, enter to start";
-            World::draw_text(frame, instructions, &self.font, WHITE, 22.0, (40.0, 150.0));
+    fn draw_start_screen(&self, scene: &mut Scene) {
+        let theme = self.theme();
+        scene.text("Battleship", theme.title, 60.0, (20.0, 0.0));
+        if let Some(settings) = &self.settings {
+            let menu = self.menu();
+            for (i, item) in menu.iter().enumerate() {
+                let color = if i == settings.selected {
+                    theme.highlight
+                } else {
+                    theme.muted
+                };
+                let text = match item {
+                    MenuItem::Continue(GameType::Hotseat) => "continue hotseat game".to_string(),
+                    MenuItem::Continue(_) => "continue game vs. computer".to_string(),
+                    MenuItem::Start(GameType::LocalNetwork) => {
+                        "start local network game".to_string()
+                    }
+                    MenuItem::Start(GameType::Ai) => "start game vs. computer".to_string(),
+                    MenuItem::Start(GameType::Hotseat) => "start hotseat game".to_string(),
+                    MenuItem::Start(GameType::ExternalBot) => "start game vs. bot".to_string(),
+                    MenuItem::Replays => "watch replays".to_string(),
+                    MenuItem::Stats => "stats".to_string(),
+                    MenuItem::Strategy => format!("computer: {}", STRATEGIES[settings.strategy]),
+                    MenuItem::KeyBindings => "key bindings".to_string(),
+                    MenuItem::Theme => format!("colors: {}", self.theme().name),
+                    MenuItem::FocusedView => {
+                        let grids = if self.focused_view { "one" } else { "both" };
+                        format!("grids shown: {}", grids)
+                    }
+                    MenuItem::Animations => {
+                        let on_off = if self.animate { "on" } else { "off" };
+                        format!("animations: {}", on_off)
+                    }
+                    MenuItem::NoTouching => {
+                        let on_off = if settings.rules.no_touching {
+                            "on"
+                        } else {
+                            "off"
+                        };
+                        format!("no-touching rule: {}", on_off)
+                    }
+                };
+                let y = MENU_TOP + i as f32 * MENU_ROW_HEIGHT;
+                scene.text(&text, color, 32.0, (GRID_MARGIN, y));
+            }
+
+            let instructions = "up and down to select, enter to start or toggle";
+            let y = MENU_TOP + menu.len() as f32 * MENU_ROW_HEIGHT + 10.0;
+            let offset = (GRID_MARGIN, y);
+            scene.text(instructions, theme.text, 22.0, offset);
+            if let Some(error) = &settings.error {
+                scene.text(error, theme.error, 22.0, (GRID_MARGIN, 520.0));
+            }
         }
     }
 
-    fn draw_info(&self, frame: &mut [u8]) {
-        let font = &self.font;
+    fn draw_info(&self, scene: &mut Scene) {
+        let theme = self.theme();
         // title text
-        World::draw_text(frame, "Battleship", font, GREEN, 60.0, (20.0, 0.0));
+        scene.text("Battleship", theme.title, 60.0, (20.0, 0.0));
+        if self.status == GameStatus::Playing(GameType::Hotseat) {
+            let player = format!("player {}", self.player_number);
+            let offset = (DESIGN_WIDTH as f32 - 20.0, 15.0);
+            scene.aligned_text(&player, theme.highlight, 30.0, offset, Align::Right);
+        }
 
         match self.this_player.status {
             PlayerStatus::Placing => {
-                World::draw_text(frame, "Place your ships!", font, WHITE, 40.0, (200.0, 60.0));
-                let height = 22.0;
-                for (i, text) in ["arrow keys to move", "space to rotate", "enter to place"]
+                scene.text("Place your ships!", theme.text, 40.0, (200.0, 60.0));
+                let height = 18.0;
+                for (i, text) in ["arrow keys to move, space to rotate", "enter to place"]
                     .iter()
                     .enumerate()
                 {
-                    let y = 100.0 + i as f32 * height;
-                    World::draw_text(frame, text, font, WHITE, height, (250.0, y));
+                    let y = 100.0 + i as f32 * (height + 2.0);
+                    scene.text(text, theme.text, height, (250.0, y));
                 }
-            }
-            PlayerStatus::Aiming => {
-                World::draw_text(frame, "Take aim!", font, WHITE, 40.0, (200.0, 60.0));
-                let height = 22.0;
-                for (i, text) in ["arrow keys to move", "space or enter to fire"]
+                let ships = &self.this_player.ships;
+                if let Some(i) = ships
                     .iter()
-                    .enumerate()
+                    .position(|ship| ship.status == ShipStatus::Placing)
                 {
-                    let y = 100.0 + i as f32 * height;
-                    World::draw_text(frame, text, font, GREEN, height, (250.0, y));
+                    let placing = format!("{} ({})", SHIP_NAMES[i], ships[i].len);
+                    scene.text(&placing, theme.highlight, 22.0, (20.0, 100.0));
+                }
+            }
+            PlayerStatus::Aiming => {
+                scene.text("Take aim!", theme.text, 40.0, (200.0, 60.0));
+                let height = 18.0;
+                let instructions = [
+                    "arrow keys to move, space or enter to fire",
+                    "or type a cell, like B7",
+                ];
+                for (i, text) in instructions.iter().enumerate() {
+                    let y = 100.0 + i as f32 * (height + 2.0);
+                    scene.text(text, theme.muted, height, (250.0, y));
+                }
+                if !self.typed_cell.is_empty() {
+                    let typed = format!("[ {:<3} ]", self.typed_cell);
+                    scene.text(&typed, theme.highlight, 30.0, (20.0, 100.0));
+                }
+                if let Some(error) = &self.typed_cell_error {
+                    scene.text(error, theme.error, 18.0, (20.0, 128.0));
                 }
             }
             PlayerStatus::Waiting => {
-                World::draw_text(
-                    frame,
-                    "Your opponent is aiming...",
-                    font,
-                    WHITE,
-                    40.0,
-                    (120.0, 90.0),
-                );
+                let waiting = match self.other_player.status {
+                    PlayerStatus::Placing => "Your opponent is placing ships...",
+                    _ => "Your opponent is aiming...",
+                };
+                scene.aligned_text(waiting, theme.text, 40.0, (CENTER_X, 90.0), Align::Center);
             }
         }
 
-        for i in 1..=10 {
-            // grid numbering
-            let offset = (
-                (GRID_MARGIN + i * CELL_WIDTH - 20) as f32,
-                TOP_MARGIN as f32 - 18.0,
+        self.draw_headings(scene, ["Your fleet", "Enemy waters"]);
+        self.draw_fleet_status(scene);
+
+        // in focused view, say how to see the other grid
+        let peek_key = self.bindings.keys_for(&Action::Peek).first().cloned();
+        if let (Some(_), Some(key)) = (self.layout.focus, peek_key) {
+            let peek = format!("{:?} to peek", key);
+            scene.text(
+                &peek,
+                theme.muted,
+                18.0,
+                (20.0, DESIGN_HEIGHT as f32 - 30.0),
             );
-            World::draw_text(frame, &i.to_string(), font, WHITE, 18.0, offset);
-            let offset = (
-                (2 * GRID_MARGIN + GRID_WIDTH + i * CELL_WIDTH - 20) as f32,
-                TOP_MARGIN as f32 - 18.0,
-            );
-            World::draw_text(frame, &i.to_string(), font, WHITE, 18.0, offset);
+        }
 
-            let letter = ('A' as u8 + i as u8 - 1) as char;
-            let offset = (
-                (GRID_MARGIN - 18) as f32,
-                (TOP_MARGIN + i * CELL_WIDTH - 22) as f32,
-            );
-            World::draw_text(frame, &letter.to_string(), font, WHITE, 18.0, offset);
-            let offset = (
-                (2 * GRID_MARGIN + GRID_WIDTH - 18) as f32,
-                (TOP_MARGIN + i * CELL_WIDTH - 22) as f32,
-            );
-            World::draw_text(frame, &letter.to_string(), font, WHITE, 18.0, offset);
+        self.draw_coordinates(scene);
+    }
+
+    /// the numbers over each grid's columns, and the letters beside its rows
+    fn draw_coordinates(&self, scene: &mut Scene) {
+        let theme = self.theme();
+        for i in 1..=10 {
+            let n = i as f32;
+            let width = self.layout.cell_width();
+            for this_player in self.layout.grids() {
+                let grid_x = self.layout.grid_x(this_player);
+                // grid numbering, centered over each column
+                let offset = (grid_x + (n - 0.5) * width, TOP_MARGIN - 18.0);
+                scene.aligned_text(&i.to_string(), theme.text, 18.0, offset, Align::Center);
+
+                // and lettering, just left of each row
+                let letter = (b'A' + i as u8 - 1) as char;
+                let offset = (grid_x - 8.0, TOP_MARGIN + (n - 0.5) * width - 11.0);
+                scene.aligned_text(&letter.to_string(), theme.text, 18.0, offset, Align::Right);
+            }
         }
     }
 
-    fn draw_end_message(&self, frame: &mut [u8]) {
-        match self.status {
+    /// which grid is which, centered over each one. this player's heading comes first
+    fn draw_headings(&self, scene: &mut Scene, headings: [&str; 2]) {
+        let theme = self.theme();
+        for this_player in self.layout.grids() {
+            let heading = if this_player {
+                headings[0]
+            } else {
+                headings[1]
+            };
+            let center = self.layout.grid_x(this_player)
+                + self.layout.cell_width() * CELL_COUNT as f32 / 2.0;
+            let offset = (center, TOP_MARGIN - 42.0);
+            scene.aligned_text(heading, theme.text, 20.0, offset, Align::Center);
+        }
+    }
+
+    /// this player's ships, with how many times each has been hit.
+    /// under this player's grid, or beside the grid in focused view
+    fn draw_fleet_status(&self, scene: &mut Scene) {
+        let theme = self.theme();
+        let width = self.layout.cell_width();
+        let (x, y) = match self.layout.focus {
+            Some(_) => {
+                let grid_x = self.layout.grid_x(true);
+                (grid_x + width * CELL_COUNT as f32 + 15.0, TOP_MARGIN)
+            }
+            None => (GRID_MARGIN, TOP_MARGIN + width * CELL_COUNT as f32 + 10.0),
+        };
+        let shots = &self.other_player.shots_taken;
+        for (i, ship) in self.this_player.ships.iter().enumerate() {
+            let hits = ship
+                .cells
+                .iter()
+                .filter(|cell| shots.contains(cell))
+                .count();
+            let (status, color) = match ship.status {
+                ShipStatus::Placing => ("placing".to_string(), theme.highlight),
+                ShipStatus::Hidden => (String::new(), theme.muted),
+                ShipStatus::Locked if World::is_sunk(ship, shots) => {
+                    ("sunk".to_string(), theme.error)
+                }
+                ShipStatus::Locked => (format!("{}/{}", hits, ship.len), theme.text),
+            };
+            let line = format!("{:<11}{}", SHIP_NAMES[i], status);
+            let offset = (x, y + i as f32 * 18.0);
+            scene.text(&line, color, 16.0, offset);
+        }
+    }
+
+    fn draw_key_bindings(&self, scene: &mut Scene) {
+        let theme = self.theme();
+        scene.text("Key bindings", theme.title, 60.0, (20.0, 0.0));
+        let selected = self.key_screen.selected;
+        for (i, action) in BINDABLE_ACTIONS.iter().enumerate() {
+            let name = match action {
+                Action::Enter => "select / place / fire",
+                Action::Space => "rotate / fire",
+                Action::Up => "up",
+                Action::Down => "down",
+                Action::Left => "left",
+                Action::Right => "right",
+                Action::FireAt(_) => "fire at",
+                Action::Peek => "peek at the other grid",
+            };
+            let keys = if i == selected && self.key_screen.listening {
+                "press a key...".to_string()
+            } else {
+                let keys: Vec<String> = self
+                    .bindings
+                    .keys_for(action)
+                    .iter()
+                    .map(|key| format!("{:?}", key))
+                    .collect();
+                keys.join(", ")
+            };
+            let color = if i == selected {
+                theme.highlight
+            } else {
+                theme.muted
+            };
+            let y = MENU_TOP + i as f32 * MENU_ROW_HEIGHT;
+            let text = format!("{}: {}", name, keys);
+            scene.text(&text, color, 28.0, (GRID_MARGIN, y));
+        }
+
+        let back_color = if selected == BINDABLE_ACTIONS.len() {
+            theme.highlight
+        } else {
+            theme.muted
+        };
+        let y = MENU_TOP + BINDABLE_ACTIONS.len() as f32 * MENU_ROW_HEIGHT;
+        let offset = (GRID_MARGIN, y);
+        scene.text("back", back_color, 28.0, offset);
+
+        let instructions = "up and down to select, enter to change a key";
+        let y = MENU_TOP + (BINDABLE_ACTIONS.len() + 1) as f32 * MENU_ROW_HEIGHT + 10.0;
+        let offset = (GRID_MARGIN, y);
+        scene.text(instructions, theme.text, 22.0, offset);
+        if let Some(error) = &self.key_screen.error {
+            scene.text(error, theme.error, 22.0, (GRID_MARGIN, y + 30.0));
+        }
+    }
+
+    fn draw_privacy_screen(&self, scene: &mut Scene) {
+        let theme = self.theme();
+        scene.text("Battleship", theme.title, 60.0, (20.0, 0.0));
+        let pass = format!("Pass the device to player {}", self.player_number);
+        scene.aligned_text(&pass, theme.text, 40.0, (CENTER_X, 200.0), Align::Center);
+        let ready = "press enter when nobody else is looking";
+        scene.aligned_text(ready, theme.text, 22.0, (CENTER_X, 260.0), Align::Center);
+    }
+
+    fn draw_connecting(&self, scene: &mut Scene) {
+        let theme = self.theme();
+        scene.text("Battleship", theme.title, 60.0, (20.0, 0.0));
+        let waiting = "Waiting for the other player...";
+        scene.aligned_text(waiting, theme.text, 40.0, (CENTER_X, 200.0), Align::Center);
+    }
+
+    /// the result, both fleets out in the open with every shot, some numbers about the game,
+    /// and what to do next
+    fn draw_end_screen(&self, scene: &mut Scene) {
+        let theme = self.theme();
+        let (message, color) = match self.status {
             GameStatus::End(GameResult::Victory) => {
-                World::draw_text(
-                    frame,
-                    "Glorious Victory!",
-                    &self.font,
-                    GREEN,
-                    60.0,
-                    (120.0, 60.0),
-                );
-                World::draw_text(
-                    frame,
-                    "press enter to restart",
-                    &self.font,
-                    WHITE,
-                    40.0,
-                    (120.0, 120.0),
-                );
+                ("Glorious Victory!".to_string(), theme.victory)
             }
             GameStatus::End(GameResult::Defeat) => {
-                World::draw_text(
-                    frame,
-                    "Ignominious Defeat!",
-                    &self.font,
-                    FLAME,
-                    60.0,
-                    (120.0, 60.0),
-                );
-                World::draw_text(
-                    frame,
-                    "press enter to restart",
-                    &self.font,
-                    WHITE,
-                    40.0,
-                    (120.0, 120.0),
-                );
+                ("Ignominious Defeat!".to_string(), theme.defeat)
             }
-            _ => {}
+            GameStatus::End(GameResult::PlayerWon(player)) => {
+                (format!("Player {} wins!", player), theme.victory)
+            }
+            _ => return,
+        };
+        scene.aligned_text(&message, color, 50.0, (CENTER_X, 5.0), Align::Center);
+        let end_screen = match &self.end_screen {
+            Some(end_screen) => end_screen,
+            None => return,
+        };
+
+        // both fleets, with every shot taken at them
+        let (this_player, other_player) = (&self.this_player, &self.other_player);
+        self.draw_fleet(scene, &this_player.ships, true);
+        self.draw_fleet(scene, &other_player.ships, false);
+        self.draw_shots_on(scene, &other_player.shots_taken, &this_player.ships, true);
+        self.draw_shots_on(scene, &this_player.shots_taken, &other_player.ships, false);
+
+        // this player first, then the other
+        let (names, owners) = match end_screen.game_type {
+            GameType::Hotseat => {
+                let (this, other) = (self.player_number, 3 - self.player_number);
+                (
+                    [format!("Player {}", this), format!("Player {}", other)],
+                    [format!("player {}'s", this), format!("player {}'s", other)],
+                )
+            }
+            GameType::Ai => (
+                ["You".to_string(), "Computer".to_string()],
+                ["your".to_string(), "the computer's".to_string()],
+            ),
+            GameType::LocalNetwork => (
+                ["You".to_string(), "Them".to_string()],
+                ["your".to_string(), "their".to_string()],
+            ),
+            GameType::ExternalBot => (
+                ["You".to_string(), "Bot".to_string()],
+                ["your".to_string(), "the bot's".to_string()],
+            ),
+        };
+        self.draw_headings(scene, [&names[0], &names[1]]);
+        self.draw_coordinates(scene);
+
+        // how each player's shooting went, under their own grid
+        let players = [(this_player, other_player), (other_player, this_player)];
+        for (i, (player, target)) in players.iter().enumerate() {
+            let shots = player.shots_taken.len();
+            let hits = player
+                .shots_taken
+                .iter()
+                .filter(|shot| target.ships.iter().any(|ship| ship.cells.contains(shot)))
+                .count();
+            let accuracy = hits * 100 / shots.max(1);
+            let line = format!("{} shots, {} hits, {}% accuracy", shots, hits, accuracy);
+            let bottom = TOP_MARGIN + self.layout.cell_width() * CELL_COUNT as f32 + 10.0;
+            let x = self.layout.grid_x(i == 0);
+            scene.text(&line, theme.text, 16.0, (x, bottom));
+        }
+
+        // the winning shot sank the last ship
+        let (winner, loser, owner) = match self.status {
+            GameStatus::End(GameResult::Defeat) => (other_player, this_player, &owners[0]),
+            _ => (this_player, other_player, &owners[1]),
+        };
+        let last_sunk = winner.shots_taken.last().and_then(|shot| {
+            loser
+                .ships
+                .iter()
+                .position(|ship| ship.cells.contains(shot))
+        });
+        let turns = this_player
+            .shots_taken
+            .len()
+            .max(other_player.shots_taken.len());
+        let summary = match last_sunk {
+            Some(i) => format!(
+                "{} turns. the last ship sunk was {} {}",
+                turns, owner, SHIP_NAMES[i]
+            ),
+            None => format!("{} turns", turns),
+        };
+        scene.aligned_text(&summary, theme.text, 18.0, (CENTER_X, 62.0), Align::Center);
+
+        let left = CENTER_X - END_CHOICE_WIDTH * END_CHOICES.len() as f32 / 2.0;
+        for (i, choice) in END_CHOICES.iter().enumerate() {
+            let text = match choice {
+                EndChoice::Rematch => "rematch",
+                EndChoice::WatchReplay => "watch replay",
+                EndChoice::Menu => "menu",
+            };
+            let color = if i == end_screen.selected {
+                theme.highlight
+            } else {
+                theme.muted
+            };
+            let center = left + (i as f32 + 0.5) * END_CHOICE_WIDTH;
+            let offset = (center, END_CHOICES_TOP);
+            scene.aligned_text(text, color, 24.0, offset, Align::Center);
+        }
+        if let Some(forfeit) = &end_screen.forfeit {
+            let text = format!("the bot forfeits: {}", forfeit);
+            let offset = (CENTER_X, END_CHOICES_TOP + 40.0);
+            scene.aligned_text(&text, theme.error, 18.0, offset, Align::Center);
+        }
+        let rematch = if end_screen.waiting_for_rematch {
+            Some("waiting for them to pick rematch too")
+        } else if end_screen.other_left {
+            Some("they left, so there's no rematch")
+        } else {
+            None
+        };
+        if let Some(text) = rematch {
+            let offset = (CENTER_X, END_CHOICES_TOP + 40.0);
+            scene.aligned_text(text, theme.muted, 18.0, offset, Align::Center);
         }
     }
 
-    fn draw_text(
+    /// a table of how games have gone, with a column for each kind of opponent
+    fn draw_stats(&self, scene: &mut Scene) {
+        let theme = self.theme();
+        scene.text("Stats", theme.title, 60.0, (20.0, 0.0));
+        let columns = [
+            (GameType::Ai, "computer"),
+            (GameType::LocalNetwork, "network"),
+            (GameType::Hotseat, "hotseat"),
+            (GameType::ExternalBot, "bot"),
+        ];
+        let records: Vec<Record> = columns
+            .iter()
+            .map(|(game_type, _)| {
+                let record = self.stats.records.get(game_type);
+                record.cloned().unwrap_or_default()
+            })
+            .collect();
+        let dash = || "-".to_string();
+        // one column of the table
+        let column = |r: &Record| {
+            [
+                r.played.to_string(),
+                r.won.to_string(),
+                r.lost.to_string(),
+                r.average_shots_to_win()
+                    .map_or_else(dash, |shots| format!("{:.1}", shots)),
+                r.accuracy()
+                    .map_or_else(dash, |a| format!("{:.0}%", a * 100.0)),
+                r.fastest_win.map_or_else(dash, |shots| shots.to_string()),
+                r.longest_streak.to_string(),
+            ]
+        };
+        let table: Vec<[String; 7]> = records.iter().map(column).collect();
+        let rows = [
+            "played",
+            "won",
+            "lost",
+            "shots to win",
+            "accuracy",
+            "fastest win",
+            "longest streak",
+        ];
+
+        let height = 20.0;
+        let header: String = columns
+            .iter()
+            .map(|(_, name)| format!("{:>10}", name))
+            .collect();
+        let header = format!("{:<15}{}", "", header);
+        scene.text(
+            &header,
+            theme.highlight,
+            height,
+            (GRID_MARGIN, MENU_TOP + 10.0),
+        );
+        for (i, name) in rows.iter().enumerate() {
+            let values: String = table
+                .iter()
+                .map(|column| format!("{:>10}", column[i]))
+                .collect();
+            let line = format!("{:<15}{}", name, values);
+            let y = MENU_TOP + 10.0 + (i + 1) as f32 * (height + 8.0);
+            scene.text(&line, theme.text, height, (GRID_MARGIN, y));
+        }
+
+        let back = "hotseat games are counted for player 1. enter to go back";
+        let y = MENU_TOP + 10.0 + (rows.len() + 1) as f32 * (height + 8.0) + 20.0;
+        scene.text(back, theme.muted, 18.0, (GRID_MARGIN, y));
+    }
+
+    /// both fleets out in the open, with every shot up to the turn being watched
+    fn draw_replay(&self, scene: &mut Scene) {
+        let theme = self.theme();
+        let viewer = &self.replay_viewer;
+        scene.text("Replay", theme.title, 60.0, (20.0, 0.0));
+        let back = "enter to go back";
+        let (replay, players) = match (&viewer.replay, viewer.turns.get(viewer.turn)) {
+            (Some(replay), Some(players)) => (replay, players),
+            _ => {
+                let message = match viewer.files.get(viewer.file) {
+                    Some(path) => format!("could not read {}", path.display()),
+                    None => "no games recorded yet".to_string(),
+                };
+                scene.aligned_text(&message, theme.text, 22.0, (CENTER_X, 200.0), Align::Center);
+                scene.aligned_text(back, theme.muted, 18.0, (CENTER_X, 240.0), Align::Center);
+                return;
+            }
+        };
+        scene.grids = true;
+
+        let turn = format!("turn {} of {}", viewer.turn, viewer.turns.len() - 1);
+        scene.text(&turn, theme.text, 40.0, (200.0, 60.0));
+        let height = 18.0;
+        let instructions = [
+            "left and right to step through the turns",
+            "up and down for other games, enter to go back",
+        ];
+        for (i, text) in instructions.iter().enumerate() {
+            let y = 100.0 + i as f32 * (height + 2.0);
+            scene.text(text, theme.muted, height, (250.0, y));
+        }
+        if let Some(name) = viewer.files[viewer.file].file_stem() {
+            let name = name.to_string_lossy();
+            scene.text(&name, theme.highlight, 22.0, (20.0, 100.0));
+        }
+
+        // player 1 is on the left, where this player's grid usually is
+        let [one, two] = players;
+        self.draw_fleet(scene, &one.ships, true);
+        self.draw_fleet(scene, &two.ships, false);
+        self.draw_shots_on(scene, &two.shots_taken, &one.ships, true);
+        self.draw_shots_on(scene, &one.shots_taken, &two.ships, false);
+        // and the shot just taken is picked out
+        if let Some(previous) = viewer.turn.checked_sub(1).map(|turn| &viewer.turns[turn]) {
+            for (i, player) in players.iter().enumerate() {
+                if player.shots_taken.len() > previous[i].shots_taken.len() {
+                    if let Some(shot) = player.shots_taken.last() {
+                        scene.fill_cell(shot, Shape::Reticle, theme.target, i == 1);
+                    }
+                }
+            }
+        }
+
+        let headings = match replay.game_type {
+            GameType::Hotseat => ["Player 1", "Player 2"],
+            GameType::Ai => ["Your fleet", "Computer's fleet"],
+            GameType::LocalNetwork => ["Your fleet", "Their fleet"],
+            GameType::ExternalBot => ["Your fleet", "Bot's fleet"],
+        };
+        self.draw_headings(scene, headings);
+        self.draw_coordinates(scene);
+    }
+
+    /// the cached bitmaps for each glyph of some text, and where they go in the frame
+    fn layout_text(&self, text: &Text) -> Vec<((i32, i32), Rc<Bitmap>)> {
+        let height = self.layout.text_height(text.height);
+        let (x, y) = self.layout.point(text.offset);
+        let width = text_width(&self.font, &text.text, height);
+        let x = match text.align {
+            Align::Left => x,
+            Align::Center => x - width / 2.0,
+            Align::Right => x - width,
+        };
+        self.glyphs
+            .layout(&self.font, &text.text, height, text.color, (x, y))
+    }
+
+    /// the frame pixels some text covers, if any
+    fn text_rect(&self, text: &Text) -> Option<Rect> {
+        let (width, height) = (self.layout.width as i32, self.layout.height as i32);
+        self.layout_text(text)
+            .iter()
+            .filter_map(|((x, y), bitmap)| {
+                let (left, top) = ((*x).max(0), (*y).max(0));
+                let right = (x + bitmap.width as i32).min(width);
+                let bottom = (y + bitmap.height as i32).min(height);
+                Some(Rect {
+                    x: left as usize,
+                    y: top as usize,
+                    width: (right - left) as usize,
+                    height: (bottom - top) as usize,
+                })
+                .filter(|_| right > left && bottom > top)
+            })
+            .reduce(|a, b| a.union(&b))
+    }
+
+    /// blend text into the frame, over whatever is already there
+    fn paint_text(&self, frame: &mut [u8], text: &Text) {
+        let (width, height) = (self.layout.width as i32, self.layout.height as i32);
+
+        for ((left, top), bitmap) in self.layout_text(text) {
+            for (i, pixel) in bitmap.pixels.iter().enumerate() {
+                let x = left + (i % bitmap.width) as i32;
+                let y = top + (i / bitmap.width) as i32;
+                // text hanging off the edge of the frame is cut off
+                if x < 0 || y < 0 || x >= width || y >= height {
+                    continue;
+                }
+                let index = (y * width + x) as usize * 4;
+                blend(&mut frame[index..index + 4], *pixel);
+            }
+        }
+    }
+
+    fn paint_cell(
+        &self,
         frame: &mut [u8],
-        text: &str,
-        font: &Font,
+        cell: &Cell,
+        shape: Shape,
         color: Color,
-        height: f32,
-        offset: (f32, f32),
+        this_player: bool,
     ) {
-        let scale = Scale {
-            x: height,
-            y: height,
-        };
-
-        let v_metrics = font.v_metrics(scale);
-        let offset = point(offset.0 + 0.0, offset.1 + v_metrics.ascent);
-
-        let glyphs: Vec<_> = font.layout(text, scale, offset).collect();
-
-        for glyph in glyphs {
-            if let Some(bounding_box) = glyph.pixel_bounding_box() {
-                glyph.draw(|x, y, v| {
-                    // Offset the position by the glyph bounding box
-                    let x_offset = x + bounding_box.min.x as u32;
-                    let y_offset = y + bounding_box.min.y as u32;
-                    let index: usize = ((y_offset * WIDTH + x_offset) * 4) as usize;
-                    // blend the colors
-                    let blended_color = [
-                        (BACKGROUND[0] as f32 * (1.0 - v) + color[0] as f32 * v) as u8,
-                        (BACKGROUND[1] as f32 * (1.0 - v) + color[1] as f32 * v) as u8,
-                        (BACKGROUND[2] as f32 * (1.0 - v) + color[2] as f32 * v) as u8,
-                        0xff,
-                    ];
-                    frame[index..index + 4].copy_from_slice(&blended_color);
-                });
+        let rect = self.layout.cell(cell, this_player);
+        let frame_size = (self.layout.width, self.layout.height);
+        match (shape, &self.sprites) {
+            (Shape::Sprite { part, turns }, Some(sprites)) => {
+                sprites.paint(part, turns, frame, frame_size, rect, color)
             }
+            _ => shape.paint(frame, frame_size, rect, color),
         }
     }
 
-    fn fill_cell(cell: &Cell, frame: &mut [u8], color: Color, this_player: bool) {
-        // cell width and height
-        let filled_len = CELL_WIDTH - 2 * CELL_MARGIN;
-        // one line _across_ within a filled cell
-        let line: Vec<u8> = repeat(color).take(filled_len).flatten().collect();
+    /// fill a rectangle of the frame with one color, clipped to the frame
+    fn fill_rect(&self, frame: &mut [u8], rect: Rect, color: Color) {
+        let width = self.layout.width;
+        let right = (rect.x + rect.width).min(width);
+        let bottom = (rect.y + rect.height).min(self.layout.height);
+        if rect.x >= right {
+            return;
+        }
+        for y in rect.y..bottom {
+            let start = (y * width + rect.x) * 4;
+            let end = (y * width + right) * 4;
+            for pixel in frame[start..end].chunks_exact_mut(4) {
+                pixel.copy_from_slice(&color);
+            }
+        }
+    }
 
-        // whose cell is this?
-        let grid_offset = if this_player {
-            GRID_MARGIN
-        } else {
-            GRID_WIDTH + 2 * GRID_MARGIN
+    /// pick what to do once the game is over
+    fn choose_after_game(&mut self, actions: &[Action]) {
+        let end_screen = match self.end_screen.as_mut() {
+            Some(end_screen) => end_screen,
+            None => return self.restart(),
         };
+        let len = END_CHOICES.len();
+        for action in actions {
+            use Action::*;
+            match action {
+                Left | Up => end_screen.selected = (end_screen.selected + len - 1) % len,
+                Right | Down => end_screen.selected = (end_screen.selected + 1) % len,
+                Enter | Space => match END_CHOICES[end_screen.selected] {
+                    EndChoice::Rematch if end_screen.game_type == GameType::LocalNetwork => {
+                        if !end_screen.other_left {
+                            end_screen.waiting_for_rematch = true;
+                            self.send_after_game(AfterGame::Rematch);
+                        }
+                        return;
+                    }
+                    EndChoice::Rematch => return self.rematch(),
+                    EndChoice::WatchReplay => {
+                        let replay = end_screen.replay.clone();
+                        self.send_after_game(AfterGame::Leave);
+                        self.restart();
+                        self.replay_viewer = ReplayViewer::open();
+                        let viewer = &mut self.replay_viewer;
+                        if let Some(file) =
+                            viewer.files.iter().position(|f| Some(f) == replay.as_ref())
+                        {
+                            viewer.file = file;
+                            viewer.load();
+                        }
+                        self.status = GameStatus::Replay;
+                        return;
+                    }
+                    EndChoice::Menu => {
+                        self.send_after_game(AfterGame::Leave);
+                        return self.restart();
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
 
-        for i in 0..filled_len {
-            let y_offset =
-                (TOP_MARGIN + i + CELL_WIDTH * cell.y as usize + CELL_MARGIN) * 4 * WIDTH as usize;
-            let x_offset = (grid_offset + CELL_WIDTH * cell.x as usize + CELL_MARGIN) * 4;
-            let cell_start = y_offset + x_offset;
-            frame[cell_start..cell_start + filled_len * 4].copy_from_slice(&line);
+    /// play the same kind of game again, with the same rules and opponent
+    fn rematch(&mut self) {
+        let game_type = match &self.end_screen {
+            Some(end_screen) => end_screen.game_type,
+            None => return,
+        };
+        let rules = self.rules;
+        // network and bot rematches carry on over the same connection, unless the bot was
+        // stopped after forfeiting
+        let stream = match self.stream.take() {
+            None if game_type == GameType::ExternalBot => match self.start_bot() {
+                Ok(stream) => Some(stream),
+                Err(e) => {
+                    self.restart();
+                    self.settings.as_mut().unwrap().error = Some(e);
+                    return;
+                }
+            },
+            stream => stream,
+        };
+        let strategy = std::mem::replace(&mut self.strategy, default_strategy());
+        self.restart();
+        self.stream = stream;
+        self.strategy = strategy;
+        self.rules = rules;
+        self.status = GameStatus::Playing(game_type);
+        self.recording = Some(Replay::new(game_type));
+    }
+
+    /// network games: tell the other player what was picked on the end screen
+    fn send_after_game(&mut self, choice: AfterGame) {
+        let is_network =
+            self.end_screen.as_ref().map(|e| e.game_type) == Some(GameType::LocalNetwork);
+        if let Some(stream) = self.stream.as_mut().filter(|_| is_network) {
+            let message = ron::ser::to_string(&choice).unwrap();
+            // (they may have closed the game already, which is as good as leaving)
+            let _ = stream.send_message(&message);
         }
     }
 
-    fn wait_for_restart(&mut self, actions: &[Action]) {
-        if actions.contains(&Action::Enter) {
-            // TODO: restart with existing stream if connected to an opponent
-            *self = World::new()
+    /// network games: wait for the other player to pick, and rematch if they want to too
+    fn await_rematch(&mut self) {
+        let answer = self
+            .stream
+            .as_mut()
+            .and_then(|stream| stream.read_message().ok())
+            .and_then(|line| ron::de::from_str(&line).ok());
+        if answer == Some(AfterGame::Rematch) {
+            return self.rematch();
+        }
+        // anything else, even the connection closing, means they've gone
+        if let Some(end_screen) = self.end_screen.as_mut() {
+            end_screen.waiting_for_rematch = false;
+            end_screen.other_left = true;
         }
     }
 
+    /// start over from the start screen, keeping the settings
+    fn restart(&mut self) {
+        let mut world = World::new(self.layout);
+        world.last_scene = self.last_scene.take();
+        world.full_redraw = self.full_redraw;
+        world.themes = std::mem::take(&mut self.themes);
+        world.theme = self.theme;
+        world.animate = self.animate;
+        world.focused_view = self.focused_view;
+        world.bot_command = self.bot_command.take();
+        world.glyphs = std::mem::take(&mut self.glyphs);
+        *self = world;
+    }
+
     fn aim(actions: &[Action], player: &mut Player, other_player: &mut Player) {
         for action in actions {
             use Action::*;
@@ -884,12 +2820,16 @@ impl World<'_> {
                 Left => {
                     player.target.shift(&Direction::Left);
                 }
-                Enter | Space => {
+                Enter | Space | FireAt(_) => {
+                    if let FireAt(cell) = action {
+                        player.target = cell.clone();
+                    }
                     if player.fire() {
                         player.status = PlayerStatus::Waiting;
                         other_player.status = PlayerStatus::Aiming;
                     }
                 }
+                Peek => {}
             }
         }
     }
@@ -899,7 +2839,7 @@ impl World<'_> {
             use Action::*;
             match action {
                 Enter => {
-                    if self.this_player.lock_ship() {
+                    if self.this_player.lock_ship(&self.rules) {
                         let next = self
                             .this_player
                             .ships
@@ -907,7 +2847,10 @@ impl World<'_> {
                             .find(|s| s.status == ShipStatus::Hidden);
                         match next {
                             Some(ship) => ship.status = ShipStatus::Placing,
-                            None => self.begin_game(),
+                            None => {
+                                self.begin_game();
+                                return;
+                            }
                         }
                     }
                 }
@@ -931,6 +2874,7 @@ impl World<'_> {
                     let ship = self.this_player.ship_to_place_mut().unwrap();
                     ship.rotate_right();
                 }
+                FireAt(_) | Peek => {}
             }
         }
     }
@@ -938,24 +2882,151 @@ impl World<'_> {
     fn select_game_type(&mut self, actions: &[Action]) {
         use Action::*;
         use GameType::*;
+        let menu = self.menu();
+        let settings = match self.settings.as_mut() {
+            Some(settings) => settings,
+            None => return,
+        };
+        let mut looks_changed = false;
         for _action in actions {
             match _action {
-                Up | Down => {
-                    let game_type = match &self.settings {
-                        None => LocalNetwork,
-                        Some(s) if s.game_type == Ai => LocalNetwork,
-                        Some(_) => Ai,
-                    };
-                    self.settings = Some(Settings { game_type });
-                }
-                Enter => {
-                    if let Some(settings) = &self.settings {
-                        self.stream = match settings.game_type {
-                            Ai => None,
-                            LocalNetwork => Some(try_connect().unwrap()),
+                Up => settings.selected = (settings.selected + menu.len() - 1) % menu.len(),
+                Down => settings.selected = (settings.selected + 1) % menu.len(),
+                Enter | Space => match menu[settings.selected] {
+                    MenuItem::Continue(_) => {
+                        if let Some(saved) = self.saved_game.take() {
+                            self.resume(saved);
+                        }
+                        return;
+                    }
+                    MenuItem::NoTouching => {
+                        settings.rules.no_touching = !settings.rules.no_touching;
+                    }
+                    MenuItem::Strategy => {
+                        settings.strategy = (settings.strategy + 1) % STRATEGIES.len();
+                    }
+                    MenuItem::Animations => {
+                        self.animate = !self.animate;
+                        looks_changed = true;
+                    }
+                    MenuItem::FocusedView => {
+                        self.focused_view = !self.focused_view;
+                        looks_changed = true;
+                    }
+                    MenuItem::Theme => {
+                        self.theme = (self.theme + 1) % self.themes.len();
+                        // every color may have changed, so nothing drawn so far can be kept
+                        self.last_scene = None;
+                        looks_changed = true;
+                    }
+                    MenuItem::Replays => {
+                        self.replay_viewer = ReplayViewer::open();
+                        self.status = GameStatus::Replay;
+                        return;
+                    }
+                    MenuItem::Stats => {
+                        self.status = GameStatus::Stats;
+                        return;
+                    }
+                    MenuItem::KeyBindings => {
+                        self.key_screen = KeyBindingScreen::default();
+                        self.status = GameStatus::KeyBindings;
+                        return;
+                    }
+                    MenuItem::Start(game_type) => {
+                        let rules = settings.rules;
+                        let strategy = by_name(STRATEGIES[settings.strategy]);
+                        self.stream = None;
+                        let started = match game_type {
+                            // (connected once a frame saying so has been drawn)
+                            Ai | Hotseat | LocalNetwork => Ok(rules),
+                            ExternalBot => self.start_bot().map(|stream| {
+                                self.stream = Some(stream);
+                                rules
+                            }),
                         };
-                        self.status = GameStatus::Playing(settings.game_type);
+                        self.rules = match started {
+                            Ok(rules) => rules,
+                            Err(e) => {
+                                self.settings.as_mut().unwrap().error = Some(e);
+                                return;
+                            }
+                        };
+                        if let Some(strategy) = strategy {
+                            self.strategy = strategy;
+                        }
+                        self.status = GameStatus::Playing(game_type);
+                        self.recording = Some(Replay::new(game_type));
+                        return;
                     }
+                },
+                _ => {}
+            }
+        }
+        if looks_changed {
+            self.save_preferences();
+        }
+    }
+
+    fn edit_key_bindings(&mut self, input: &dyn Input, actions: &[Action]) {
+        let screen = &mut self.key_screen;
+        if screen.listening {
+            if let Some(key) = pressed_key(input) {
+                let action = BINDABLE_ACTIONS[screen.selected].clone();
+                if self.bindings.rebind(key, action) {
+                    screen.error = self.bindings.save().err();
+                } else {
+                    screen.error = Some(format!(
+                        "can't use {:?}, it's the only key for enter, up or down",
+                        key
+                    ));
+                }
+                screen.listening = false;
+            }
+            return;
+        }
+
+        // one entry per action, plus 'back'
+        let len = BINDABLE_ACTIONS.len() + 1;
+        for action in actions {
+            use Action::*;
+            match action {
+                Up => screen.selected = (screen.selected + len - 1) % len,
+                Down => screen.selected = (screen.selected + 1) % len,
+                Enter | Space if screen.selected == BINDABLE_ACTIONS.len() => {
+                    self.status = GameStatus::Starting;
+                    return;
+                }
+                Enter | Space => {
+                    screen.listening = true;
+                    return;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// step through the turns of a replay, or switch to another one
+    fn watch_replay(&mut self, actions: &[Action]) {
+        let viewer = &mut self.replay_viewer;
+        let last_turn = viewer.turns.len().saturating_sub(1);
+        for action in actions {
+            use Action::*;
+            match action {
+                Right => viewer.turn = (viewer.turn + 1).min(last_turn),
+                Left => viewer.turn = viewer.turn.saturating_sub(1),
+                Up | Down if viewer.files.len() > 1 => {
+                    let len = viewer.files.len();
+                    viewer.file = match action {
+                        Up => (viewer.file + len - 1) % len,
+                        _ => (viewer.file + 1) % len,
+                    };
+                    viewer.load();
+                    return;
+                }
+                Enter | Space => {
+                    self.status = GameStatus::Starting;
+                    return;
                 }
                 _ => {}
             }
@@ -975,10 +3046,13 @@ impl World<'_> {
             .ships
             .iter()
             .all(|ship| World::is_sunk(ship, &self.this_player.shots_taken));
+        let hotseat = self.status == GameStatus::Playing(GameType::Hotseat);
         if loss {
             self.status = GameStatus::End(GameResult::Defeat);
         }
-        if win {
+        if win && hotseat {
+            self.status = GameStatus::End(GameResult::PlayerWon(self.player_number));
+        } else if win {
             self.status = GameStatus::End(GameResult::Victory);
         }
     }
@@ -987,3 +3061,169 @@ impl World<'_> {
         ship.cells.iter().all(|cell| shots.contains(cell))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::placement::Placement;
+
+    #[test]
+    fn parse_cell() {
+        assert_eq!(Cell::parse("B7"), Ok(Cell { x: 6, y: 1 }));
+        assert_eq!(Cell::parse("A1"), Ok(Cell { x: 0, y: 0 }));
+        assert_eq!(Cell::parse("J10"), Ok(Cell { x: 9, y: 9 }));
+    }
+
+    #[test]
+    fn parse_lowercase_cell() {
+        assert_eq!(Cell::parse("b7"), Ok(Cell { x: 6, y: 1 }));
+    }
+
+    #[test]
+    fn parse_ten_as_the_last_column() {
+        assert_eq!(Cell::parse("A10"), Ok(Cell { x: 9, y: 0 }));
+    }
+
+    #[test]
+    fn parse_out_of_range_cell() {
+        for text in ["K1", "A0", "A11", "J100"] {
+            assert!(Cell::parse(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn parse_junk() {
+        for text in ["", "B", "7", "7B", "B7x", "B-7", "hello"] {
+            assert!(Cell::parse(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn replay_turns_round_trip() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let rules = Rules::default();
+        let (mut one, mut two) = (Player::new(), Player::new());
+        for player in [&mut one, &mut two] {
+            let fleet = Placement::Uniform
+                .place(&SHIP_LENGTHS, false, &mut rng)
+                .unwrap();
+            player.ships = Ship::fleet(fleet, &SHIP_LENGTHS, &rules).unwrap();
+        }
+        let mut replay = Replay::new(GameType::Hotseat);
+        replay.fleets = [one.ships.clone(), two.ships.clone()];
+        let moves = vec![
+            (1, vec![Action::Up, Action::Left, Action::Enter]),
+            (2, vec![Action::FireAt(Cell { x: 0, y: 0 })]),
+            (1, vec![Action::Right]),
+            (1, vec![Action::Down, Action::Space]),
+            (2, vec![Action::FireAt(Cell { x: 9, y: 9 })]),
+            // the same cell again isn't another shot
+            (2, vec![Action::FireAt(Cell { x: 9, y: 9 })]),
+        ];
+        for (number, actions) in moves {
+            let (shooter, target) = match number {
+                1 => (&mut one, &mut two),
+                _ => (&mut two, &mut one),
+            };
+            World::aim(&actions, shooter, target);
+            replay.moves.push((number, actions));
+        }
+
+        let saved = ron::ser::to_string(&replay).unwrap();
+        let loaded: Replay = ron::de::from_str(&saved).unwrap();
+        let turns = loaded.turns();
+        assert_eq!(turns.len(), 5);
+        assert!(turns[0].iter().all(|player| player.shots_taken.is_empty()));
+        let [last_one, last_two] = turns.last().unwrap();
+        assert_eq!(last_one.ships, one.ships);
+        assert_eq!(last_two.ships, two.ships);
+        assert_eq!(last_one.shots_taken, one.shots_taken);
+        assert_eq!(last_two.shots_taken, two.shots_taken);
+        assert_eq!(
+            one.shots_taken,
+            vec![Cell { x: 3, y: 4 }, Cell { x: 5, y: 6 }]
+        );
+    }
+
+    /// a horizontal ship's cells, bow on the left
+    fn across(x: u8, y: u8, len: u8) -> Vec<Cell> {
+        (x..x + len).map(|x| Cell { x, y }).collect()
+    }
+
+    /// a fleet of the usual lengths, with the ships on every other row
+    fn spaced_fleet() -> Vec<Vec<Cell>> {
+        SHIP_LENGTHS
+            .iter()
+            .enumerate()
+            .map(|(i, &len)| across(0, 2 * i as u8, len))
+            .collect()
+    }
+
+    #[test]
+    fn fleet_that_could_be_placed_by_hand() {
+        let rules = Rules { no_touching: true };
+        let ships = Ship::fleet(spaced_fleet(), &SHIP_LENGTHS, &rules).unwrap();
+        assert_eq!(ships.len(), SHIP_LENGTHS.len());
+        assert!(ships.iter().all(|ship| ship.status == ShipStatus::Locked));
+    }
+
+    #[test]
+    fn fleet_with_the_wrong_lengths() {
+        let rules = Rules::default();
+        let mut fleet = spaced_fleet();
+        fleet.swap(0, 4);
+        assert!(Ship::fleet(fleet, &SHIP_LENGTHS, &rules).is_err());
+        let mut fleet = spaced_fleet();
+        fleet.pop();
+        assert!(Ship::fleet(fleet, &SHIP_LENGTHS, &rules).is_err());
+    }
+
+    #[test]
+    fn fleet_with_overlapping_ships() {
+        let rules = Rules::default();
+        let mut fleet = spaced_fleet();
+        fleet[1] = across(1, 0, 3);
+        assert!(Ship::fleet(fleet, &SHIP_LENGTHS, &rules).is_err());
+        // the two ships of length 4, right on top of each other
+        let mut fleet = spaced_fleet();
+        fleet[3] = fleet[2].clone();
+        assert!(Ship::fleet(fleet, &SHIP_LENGTHS, &rules).is_err());
+    }
+
+    #[test]
+    fn fleet_with_touching_ships() {
+        let mut fleet = spaced_fleet();
+        fleet[1] = across(0, 1, 3);
+        let touching = Ship::fleet(fleet.clone(), &SHIP_LENGTHS, &Rules { no_touching: false });
+        assert!(touching.is_ok());
+        let no_touching = Ship::fleet(fleet, &SHIP_LENGTHS, &Rules { no_touching: true });
+        assert!(no_touching.is_err());
+    }
+
+    #[test]
+    fn fleet_off_the_grid() {
+        let mut fleet = spaced_fleet();
+        fleet[4] = across(6, 8, 5);
+        assert!(Ship::fleet(fleet, &SHIP_LENGTHS, &Rules::default()).is_err());
+    }
+
+    #[test]
+    fn fleet_with_a_bent_ship() {
+        let rules = Rules::default();
+        let mut fleet = spaced_fleet();
+        fleet[1] = vec![
+            Cell { x: 0, y: 2 },
+            Cell { x: 1, y: 2 },
+            Cell { x: 1, y: 3 },
+        ];
+        assert!(Ship::fleet(fleet, &SHIP_LENGTHS, &rules).is_err());
+        // or one with a gap in it
+        let mut fleet = spaced_fleet();
+        fleet[1] = vec![
+            Cell { x: 0, y: 2 },
+            Cell { x: 1, y: 2 },
+            Cell { x: 3, y: 2 },
+        ];
+        assert!(Ship::fleet(fleet, &SHIP_LENGTHS, &rules).is_err());
+    }
+}
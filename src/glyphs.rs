@@ -0,0 +1,121 @@
+use rusttype::{point, Font, GlyphId, Scale};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::colors::Color;
+
+/// how wide some text is, in pixels, from the start of the first glyph to the end of the last
+pub fn text_width(font: &Font, text: &str, height: f32) -> f32 {
+    let scale = Scale {
+        x: height,
+        y: height,
+    };
+    font.layout(text, scale, point(0.0, 0.0))
+        .last()
+        .map_or(0.0, |glyph| {
+            glyph.position().x + glyph.unpositioned().h_metrics().advance_width
+        })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    id: GlyphId,
+    /// the text height's bits, since f32 isn't hashable
+    height: u32,
+    color: Color,
+}
+
+/// a rasterized glyph, colored in, with its coverage as the alpha channel
+#[derive(Debug)]
+pub struct Bitmap {
+    /// where the bitmap's top left corner is, relative to the glyph's position
+    pub left: i32,
+    pub top: i32,
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Color>,
+}
+
+/// glyphs are rasterized once per size and color, then copied into the frame from here
+#[derive(Debug, Default)]
+pub struct GlyphCache {
+    glyphs: RefCell<HashMap<GlyphKey, Rc<Bitmap>>>,
+}
+
+impl GlyphCache {
+    /// the bitmaps for each glyph of some text, and where they go in the frame
+    /// `offset` is the top left corner of the text, in frame pixels
+    pub fn layout(
+        &self,
+        font: &Font,
+        text: &str,
+        height: f32,
+        color: Color,
+        offset: (f32, f32),
+    ) -> Vec<((i32, i32), Rc<Bitmap>)> {
+        let scale = Scale {
+            x: height,
+            y: height,
+        };
+        let v_metrics = font.v_metrics(scale);
+        let offset = point(offset.0, offset.1 + v_metrics.ascent);
+
+        font.layout(text, scale, offset)
+            .map(|glyph| {
+                let position = glyph.position();
+                let key = GlyphKey {
+                    id: glyph.id(),
+                    height: height.to_bits(),
+                    color,
+                };
+                let bitmap = self
+                    .glyphs
+                    .borrow_mut()
+                    .entry(key)
+                    .or_insert_with(|| {
+                        // rasterize at a whole pixel, so the bitmap can be reused anywhere
+                        let glyph = glyph.unpositioned().clone().positioned(point(0.0, 0.0));
+                        Rc::new(GlyphCache::rasterize(&glyph, color))
+                    })
+                    .clone();
+                let x = position.x.round() as i32 + bitmap.left;
+                let y = position.y.round() as i32 + bitmap.top;
+                ((x, y), bitmap)
+            })
+            .collect()
+    }
+
+    fn rasterize(glyph: &rusttype::PositionedGlyph, color: Color) -> Bitmap {
+        match glyph.pixel_bounding_box() {
+            Some(bounding_box) => {
+                let width = bounding_box.width() as usize;
+                let height = bounding_box.height() as usize;
+                let mut pixels = vec![[color[0], color[1], color[2], 0]; width * height];
+                glyph.draw(|x, y, v| {
+                    pixels[y as usize * width + x as usize][3] = (v * 255.0) as u8;
+                });
+                Bitmap {
+                    left: bounding_box.min.x,
+                    top: bounding_box.min.y,
+                    width,
+                    height,
+                    pixels,
+                }
+            }
+            // e.g. spaces
+            None => Bitmap {
+                left: 0,
+                top: 0,
+                width: 0,
+                height: 0,
+                pixels: Vec::new(),
+            },
+        }
+    }
+
+    /// forget every glyph, e.g. when the text sizes all change
+    pub fn clear(&self) {
+        self.glyphs.borrow_mut().clear();
+    }
+}
@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use winit::event::VirtualKeyCode;
+use winit_input_helper::{TextChar, WinitInputHelper};
+
+use crate::files::{load_ron, save_ron};
+use crate::game::Action;
+
+/// actions that can be bound to keys, in the order they're listed on the key bindings screen
+pub(crate) const BINDABLE_ACTIONS: [Action; 7] = [
+    Action::Enter,
+    Action::Space,
+    Action::Up,
+    Action::Down,
+    Action::Left,
+    Action::Right,
+    Action::Peek,
+];
+
+/// actions that always need a key, since there's no other way around the menus
+const REQUIRED_ACTIONS: [Action; 3] = [Action::Enter, Action::Up, Action::Down];
+
+/// keys that can be bound to an action
+/// (escape is left out, since it always closes the game)
+const BINDABLE_KEYS: [VirtualKeyCode; 48] = {
+    use VirtualKeyCode::*;
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, Key0, Key1,
+        Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Up, Down, Left, Right, Return, Space, Tab,
+        Back, LShift, RShift, LControl, RControl,
+    ]
+};
+
+/// which keys make which actions, saved as ron in the config directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct KeyBindings {
+    pub(crate) keys: BTreeMap<VirtualKeyCode, Action>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        use VirtualKeyCode::*;
+        let keys = [
+            (Return, Action::Enter),
+            (Space, Action::Space),
+            (Up, Action::Up),
+            (Down, Action::Down),
+            (Right, Action::Right),
+            (Left, Action::Left),
+            (Tab, Action::Peek),
+        ];
+        KeyBindings {
+            keys: keys.iter().cloned().collect(),
+        }
+    }
+}
+
+impl KeyBindings {
+    fn path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_default()
+            .join("battleship")
+            .join("keys.ron")
+    }
+
+    /// read the bindings from the config file, falling back to the default keys
+    pub(crate) fn load() -> Result<Self, String> {
+        Ok(load_ron(&KeyBindings::path())?.unwrap_or_default())
+    }
+
+    pub(crate) fn save(&self) -> Result<(), String> {
+        save_ron(&KeyBindings::path(), self)
+    }
+
+    /// all keys that make this action, for display
+    pub(crate) fn keys_for(&self, action: &Action) -> Vec<VirtualKeyCode> {
+        self.keys
+            .iter()
+            .filter(|(_, a)| *a == action)
+            .map(|(&key, _)| key)
+            .collect()
+    }
+
+    /// make the one key do this action, in place of whatever keys did it before. if the key
+    /// already did something else, that action takes over the old keys, so the two swap.
+    /// returns false, and changes nothing, if it would leave a key the menus need unbound
+    pub(crate) fn rebind(&mut self, key: VirtualKeyCode, action: Action) -> bool {
+        let mut keys = self.keys.clone();
+        let old_keys = self.keys_for(&action);
+        keys.retain(|_, a| *a != action);
+        if let Some(previous) = keys.insert(key, action.clone()) {
+            for old_key in old_keys.into_iter().filter(|&old_key| old_key != key) {
+                keys.insert(old_key, previous.clone());
+            }
+        }
+        let rebound = KeyBindings { keys };
+        if !REQUIRED_ACTIONS
+            .iter()
+            .all(|a| !rebound.keys_for(a).is_empty())
+        {
+            return false;
+        }
+        *self = rebound;
+        true
+    }
+}
+
+/// the ui state of the key bindings screen
+#[derive(Debug, Default)]
+pub(crate) struct KeyBindingScreen {
+    /// index into `BINDABLE_ACTIONS`, or one past the end for 'back'
+    pub(crate) selected: usize,
+    /// waiting for the player to press the new key for the selected action
+    pub(crate) listening: bool,
+    /// why the last key pressed wasn't bound, if it wasn't
+    pub(crate) error: Option<String>,
+}
+
+/// letter and number keys, which can also be typed as text
+pub(crate) fn is_text_key(key: VirtualKeyCode) -> bool {
+    use VirtualKeyCode::*;
+    // the number keys go 1 through 9, then 0, straight into A through Z
+    (Key1..=Z).contains(&key) || key == Back
+}
+
+/// what `World` reads of the keyboard and mouse on each update, from the window or the terminal
+pub trait Input {
+    fn key_pressed(&self, key: VirtualKeyCode) -> bool;
+    /// characters typed since the last update
+    fn text(&self) -> Vec<TextChar>;
+    /// 0 is the left button, 1 the right
+    fn mouse_pressed(&self, button: usize) -> bool;
+    fn mouse_released(&self, button: usize) -> bool;
+    fn scroll_diff(&self) -> f32;
+}
+
+impl Input for WinitInputHelper {
+    fn key_pressed(&self, key: VirtualKeyCode) -> bool {
+        WinitInputHelper::key_pressed(self, key)
+    }
+
+    fn text(&self) -> Vec<TextChar> {
+        WinitInputHelper::text(self)
+    }
+
+    fn mouse_pressed(&self, button: usize) -> bool {
+        WinitInputHelper::mouse_pressed(self, button)
+    }
+
+    fn mouse_released(&self, button: usize) -> bool {
+        WinitInputHelper::mouse_released(self, button)
+    }
+
+    fn scroll_diff(&self) -> f32 {
+        WinitInputHelper::scroll_diff(self)
+    }
+}
+
+/// the letter or number key for a character, if it's one
+pub(crate) fn key_for_char(c: char) -> Option<VirtualKeyCode> {
+    // `BINDABLE_KEYS` starts with A through Z, then 0 through 9
+    match c.to_ascii_lowercase() {
+        c @ 'a'..='z' => Some(BINDABLE_KEYS[(c as u8 - b'a') as usize]),
+        c @ '0'..='9' => Some(BINDABLE_KEYS[26 + (c as u8 - b'0') as usize]),
+        _ => None,
+    }
+}
+
+/// the first bindable key pressed this update, if any
+pub(crate) fn pressed_key(input: &dyn Input) -> Option<VirtualKeyCode> {
+    BINDABLE_KEYS
+        .iter()
+        .cloned()
+        .find(|&key| input.key_pressed(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use VirtualKeyCode::*;
+
+    #[test]
+    fn rebind_a_free_key() {
+        let mut bindings = KeyBindings::default();
+        assert!(bindings.rebind(W, Action::Up));
+        assert_eq!(bindings.keys_for(&Action::Up), vec![W]);
+        assert!(!bindings.keys.contains_key(&Up));
+    }
+
+    #[test]
+    fn rebind_swaps_with_the_other_action() {
+        let mut bindings = KeyBindings::default();
+        assert!(bindings.rebind(Down, Action::Up));
+        assert_eq!(bindings.keys_for(&Action::Up), vec![Down]);
+        assert_eq!(bindings.keys_for(&Action::Down), vec![Up]);
+    }
+
+    #[test]
+    fn rebind_refuses_to_unbind_the_menu_keys() {
+        for (key, action) in [
+            (Return, Action::Enter),
+            (Up, Action::Up),
+            (Down, Action::Down),
+        ] {
+            let mut bindings = KeyBindings {
+                keys: [
+                    (Return, Action::Enter),
+                    (Up, Action::Up),
+                    (Down, Action::Down),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            };
+            let before = bindings.clone();
+            assert!(!bindings.rebind(key, Action::Space), "{:?}", action);
+            assert_eq!(bindings, before);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use crate::game::{Cell, CELL_COUNT};
+
+// the screen is designed at 720x600, then scaled to fit whatever the window actually is
+pub const DESIGN_WIDTH: u32 = 720;
+pub const DESIGN_HEIGHT: u32 = 600;
+pub const CENTER_X: f32 = DESIGN_WIDTH as f32 / 2.0;
+pub const TOP_MARGIN: f32 = 190.0;
+pub const GRID_WIDTH: f32 = 301.0;
+pub const GRID_MARGIN: f32 = 40.0;
+pub const CELL_WIDTH: f32 = 30.0;
+/// cells are bigger when only one grid is shown
+pub const FOCUSED_CELL_WIDTH: f32 = 40.0;
+pub const CELL_MARGIN: f32 = 4.0;
+
+/// a rectangle of frame pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
+
+    /// the smallest rectangle covering both
+    pub fn union(&self, other: &Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Rect {
+            x,
+            y,
+            width: (self.x + self.width).max(other.x + other.width) - x,
+            height: (self.y + self.height).max(other.y + other.height) - y,
+        }
+    }
+}
+
+/// where things go in the frame, for the current window size and dpi
+///
+/// everything is laid out in design coordinates (a 720x600 screen), then scaled
+/// evenly to fit the frame and centered in it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    /// frame size, in physical pixels
+    pub width: usize,
+    pub height: usize,
+    /// frame pixels per design pixel
+    pub scale: f32,
+    /// where the design's top left corner ends up in the frame
+    origin: (f32, f32),
+    /// grid lines are at least one physical pixel, and thicker on high dpi screens
+    line_width: usize,
+    /// which grid is shown on its own, bigger and in the middle, if only one is.
+    /// `true` for this player's grid
+    pub focus: Option<bool>,
+}
+
+impl Layout {
+    pub fn new(width: u32, height: u32, scale_factor: f64) -> Self {
+        let scale = (width as f32 / DESIGN_WIDTH as f32).min(height as f32 / DESIGN_HEIGHT as f32);
+        let origin = (
+            (width as f32 - DESIGN_WIDTH as f32 * scale) / 2.0,
+            (height as f32 - DESIGN_HEIGHT as f32 * scale) / 2.0,
+        );
+        Layout {
+            width: width as usize,
+            height: height as usize,
+            scale,
+            origin,
+            line_width: (scale_factor.round() as usize).max(1),
+            focus: None,
+        }
+    }
+
+    /// design coordinates to frame coordinates
+    pub fn point(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        (
+            self.origin.0 + x * self.scale,
+            self.origin.1 + y * self.scale,
+        )
+    }
+
+    /// frame coordinates to design coordinates
+    pub fn design_point(&self, (x, y): (usize, usize)) -> (f32, f32) {
+        (
+            (x as f32 - self.origin.0) / self.scale,
+            (y as f32 - self.origin.1) / self.scale,
+        )
+    }
+
+    /// a text height in design pixels, scaled to the frame
+    pub fn text_height(&self, height: f32) -> f32 {
+        height * self.scale
+    }
+
+    /// the frame rectangle covering a rectangle in design coordinates
+    pub fn rect(&self, (x, y): (f32, f32), (width, height): (f32, f32)) -> Rect {
+        let (left, top) = self.point((x, y));
+        let (right, bottom) = self.point((x + width, y + height));
+        let (left, top) = (left.round() as usize, top.round() as usize);
+        Rect {
+            x: left,
+            y: top,
+            width: (right.round() as usize).max(left + 1) - left,
+            height: (bottom.round() as usize).max(top + 1) - top,
+        }
+    }
+
+    /// whether a grid is on screen at all
+    pub fn shows(&self, this_player: bool) -> bool {
+        self.focus.is_none_or(|focus| focus == this_player)
+    }
+
+    /// the grids on screen
+    pub fn grids(&self) -> impl Iterator<Item = bool> + '_ {
+        [true, false]
+            .iter()
+            .cloned()
+            .filter(move |&this_player| self.shows(this_player))
+    }
+
+    /// design width of a grid cell
+    pub fn cell_width(&self) -> f32 {
+        match self.focus {
+            Some(_) => FOCUSED_CELL_WIDTH,
+            None => CELL_WIDTH,
+        }
+    }
+
+    /// design x coordinate of the left edge of a grid
+    pub fn grid_x(&self, this_player: bool) -> f32 {
+        match (self.focus, this_player) {
+            (Some(_), _) => CENTER_X - FOCUSED_CELL_WIDTH * CELL_COUNT as f32 / 2.0,
+            (None, true) => GRID_MARGIN,
+            (None, false) => GRID_WIDTH + 2.0 * GRID_MARGIN,
+        }
+    }
+
+    /// the whole grid, lines included. this player's is on the left when both are shown
+    pub fn grid(&self, this_player: bool) -> Rect {
+        let x = self.grid_x(this_player);
+        let size = self.cell_width() * CELL_COUNT as f32;
+        let mut rect = self.rect((x, TOP_MARGIN), (size, size));
+        rect.width += self.line_width;
+        rect.height += self.line_width;
+        rect
+    }
+
+    /// the inside of a cell, between the grid lines
+    pub fn cell(&self, cell: &Cell, this_player: bool) -> Rect {
+        let width = self.cell_width();
+        let x = self.grid_x(this_player) + width * cell.x as f32;
+        let y = TOP_MARGIN + width * cell.y as f32;
+        let mut rect = self.rect((x, y), (width, width));
+        rect.x += self.line_width;
+        rect.y += self.line_width;
+        rect.width = rect.width.saturating_sub(self.line_width).max(1);
+        rect.height = rect.height.saturating_sub(self.line_width).max(1);
+        rect
+    }
+
+    /// which grid cell is at this frame pixel, if any, and whether it's on this player's grid
+    pub fn cell_at(&self, x: usize, y: usize) -> Option<(Cell, bool)> {
+        let (x, y) = self.design_point((x, y));
+        let width = self.cell_width();
+        let grid_len = width * CELL_COUNT as f32;
+        if y < TOP_MARGIN || y >= TOP_MARGIN + grid_len {
+            return None;
+        }
+        self.grids()
+            .map(|this_player| (self.grid_x(this_player), this_player))
+            .find(|(grid_x, _)| x >= *grid_x && x < grid_x + grid_len)
+            .map(|(grid_x, this_player)| {
+                let cell = Cell {
+                    x: ((x - grid_x) / width) as u8,
+                    y: ((y - TOP_MARGIN) / width) as u8,
+                };
+                (cell, this_player)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(x: u8, y: u8, this_player: bool) -> Option<(Cell, bool)> {
+        Some((Cell { x, y }, this_player))
+    }
+
+    #[test]
+    fn cell_at_grid_edges() {
+        let layout = Layout::new(DESIGN_WIDTH, DESIGN_HEIGHT, 1.0);
+        assert_eq!(layout.cell_at(40, 190), cell(0, 0, true));
+        assert_eq!(layout.cell_at(339, 489), cell(9, 9, true));
+        assert_eq!(layout.cell_at(381, 190), cell(0, 0, false));
+        assert_eq!(layout.cell_at(680, 489), cell(9, 9, false));
+        assert_eq!(layout.cell_at(39, 190), None);
+        assert_eq!(layout.cell_at(40, 189), None);
+        assert_eq!(layout.cell_at(340, 300), None);
+        assert_eq!(layout.cell_at(380, 300), None);
+        assert_eq!(layout.cell_at(681, 300), None);
+        assert_eq!(layout.cell_at(100, 490), None);
+    }
+
+    #[test]
+    fn cell_at_after_a_resize() {
+        let layout = Layout::new(2 * DESIGN_WIDTH, 2 * DESIGN_HEIGHT, 2.0);
+        assert_eq!(layout.cell_at(80, 380), cell(0, 0, true));
+        assert_eq!(layout.cell_at(79, 380), None);
+        assert_eq!(layout.cell_at(1361, 979), cell(9, 9, false));
+        assert_eq!(layout.cell_at(1362, 980), None);
+
+        // a wider window keeps the design's shape, centered with bars at the sides
+        let layout = Layout::new(2 * DESIGN_WIDTH, DESIGN_HEIGHT, 1.0);
+        assert_eq!(layout.cell_at(400, 190), cell(0, 0, true));
+        assert_eq!(layout.cell_at(399, 190), None);
+        assert_eq!(layout.cell_at(1040, 489), cell(9, 9, false));
+    }
+}
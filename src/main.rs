@@ -1,42 +1,127 @@
-#![feature(derive_default_enum)]
-
 use pixels::{Error, Pixels, SurfaceTexture};
+use std::convert::TryFrom;
+use std::env;
+use std::time::{Duration, Instant};
 use winit::dpi::LogicalSize;
 use winit::event::{Event, VirtualKeyCode};
 use winit::event_loop::{ControlFlow, EventLoop};
-use winit::window::WindowBuilder;
+use winit::window::{Fullscreen, WindowBuilder};
 use winit_input_helper::WinitInputHelper;
 
+mod animation;
+mod bot;
 mod colors;
 mod connection;
+mod files;
 mod game;
+mod glyphs;
+mod keys;
+mod layout;
+mod placement;
+mod scene;
+mod shapes;
+mod simulation;
+mod sprites;
+mod stats;
+mod strategy;
+mod tui;
 
-use crate::game::{World, HEIGHT, WIDTH};
+use crate::game::{Rules, World};
+use crate::layout::{Layout, DESIGN_HEIGHT, DESIGN_WIDTH};
 
 fn main() -> Result<(), Error> {
+    // --simulate <games> plays computer players against each other with no window,
+    // --players <name>,<name> picks which ones, --seed <seed> plays out the same games again,
+    // and --no-touching plays by that rule
+    if let Some(games) = arg_value("--simulate") {
+        let games = match games.parse() {
+            Ok(games) => games,
+            Err(_) => {
+                println!("--simulate takes a number of games, not {}", games);
+                return Ok(());
+            }
+        };
+        let seed = match arg_value("--seed").map(|seed| seed.parse()) {
+            Some(Ok(seed)) => seed,
+            Some(Err(_)) => {
+                println!("--seed takes a whole number");
+                return Ok(());
+            }
+            None => rand::random(),
+        };
+        let players = arg_value("--players").unwrap_or_else(|| "easy,easy".to_string());
+        let strategies: Option<Vec<_>> = players.split(',').map(strategy::by_name).collect();
+        let mut strategies = match strategies.map(<[_; 2]>::try_from) {
+            Some(Ok(strategies)) => strategies,
+            _ => {
+                let names = strategy::STRATEGIES.join(", ");
+                println!("--players takes two of {}, like easy,expert", names);
+                return Ok(());
+            }
+        };
+        let rules = Rules {
+            no_touching: env::args().any(|arg| arg == "--no-touching"),
+        };
+        simulation::run(games, seed, &rules, &mut strategies);
+        return Ok(());
+    }
+
+    // --tui plays in the terminal instead of a window
+    if env::args().any(|arg| arg == "--tui") {
+        let mut world = World::new(Layout::new(DESIGN_WIDTH, DESIGN_HEIGHT, 1.0));
+        world.set_bot_command(arg_value("--bot"));
+        if let Err(e) = tui::run(&mut world) {
+            println!("the terminal stopped working, {}", e);
+        }
+        return Ok(());
+    }
+
     let event_loop = EventLoop::new();
     let mut input = WinitInputHelper::new();
     let window = {
-        let size = LogicalSize::new(WIDTH as f64, HEIGHT as f64);
+        let size = LogicalSize::new(DESIGN_WIDTH as f64, DESIGN_HEIGHT as f64);
+        let min_size = LogicalSize::new(DESIGN_WIDTH as f64 / 2.0, DESIGN_HEIGHT as f64 / 2.0);
         WindowBuilder::new()
             .with_title("Battleship!")
             .with_inner_size(size)
-            .with_min_inner_size(size)
+            .with_min_inner_size(min_size)
             .build(&event_loop)
             .unwrap()
     };
 
+    // the frame buffer is always the same size as the window, so it renders at native resolution
+    let window_size = window.inner_size();
     let mut pixels = {
-        let window_size = window.inner_size();
         let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, &window);
-        Pixels::new(WIDTH, HEIGHT, surface_texture)?
+        Pixels::new(window_size.width, window_size.height, surface_texture)?
     };
-    let mut world = World::new();
+    let layout = Layout::new(window_size.width, window_size.height, window.scale_factor());
+    let mut world = World::new(layout);
+
+    // --frame-times prints how long rendering takes, and --full-redraw turns off
+    // redrawing only what changed, to compare against
+    let frame_times = env::args().any(|arg| arg == "--frame-times");
+    world.set_full_redraw(env::args().any(|arg| arg == "--full-redraw"));
+    // --bot <command> adds a game against a program that plays over stdin and stdout.
+    // see bot.rs for what it's sent and what it should answer
+    world.set_bot_command(arg_value("--bot"));
+    let mut render_time = Duration::ZERO;
+    let mut frames = 0;
 
     event_loop.run(move |event, _, control_flow| {
         // Draw the current frame
         if let Event::RedrawRequested(_) = event {
+            let start = Instant::now();
             world.render(pixels.get_frame());
+            if frame_times {
+                render_time += start.elapsed();
+                frames += 1;
+                if frames == 100 {
+                    println!("average render time: {:?}", render_time / frames);
+                    render_time = Duration::ZERO;
+                    frames = 0;
+                }
+            }
             if pixels
                 .render()
                 .map_err(|e| println!("pixels.render() failed: {}", e))
@@ -50,19 +135,55 @@ fn main() -> Result<(), Error> {
         // Handle input events
         if input.update(&event) {
             // Close events
-            if input.key_pressed(VirtualKeyCode::Escape) || input.quit() {
+            // (escape cancels typing a cell first)
+            let escape = input.key_pressed(VirtualKeyCode::Escape) && !world.is_typing();
+            if escape || input.quit() {
+                if let Err(e) = world.save_game() {
+                    println!("{}", e);
+                }
                 *control_flow = ControlFlow::Exit;
                 return;
             }
 
-            // Resize the window
-            if let Some(size) = input.window_resized() {
-                pixels.resize_surface(size.width, size.height);
+            // Save the game, to continue it next time
+            if input.key_pressed(VirtualKeyCode::F5) {
+                if let Err(e) = world.save_game() {
+                    world.notify(e);
+                }
+            }
+
+            // Toggle fullscreen
+            if input.key_pressed(VirtualKeyCode::F11) {
+                let fullscreen = match window.fullscreen() {
+                    Some(_) => None,
+                    None => Some(Fullscreen::Borderless(None)),
+                };
+                window.set_fullscreen(fullscreen);
+            }
+
+            // Resize the window, and lay the game out again for the new size
+            if input.window_resized().is_some() || input.scale_factor_changed().is_some() {
+                let size = window.inner_size();
+                // (minimized windows have no size to draw into)
+                if size.width > 0 && size.height > 0 {
+                    pixels.resize_surface(size.width, size.height);
+                    pixels.resize_buffer(size.width, size.height);
+                    let layout = Layout::new(size.width, size.height, window.scale_factor());
+                    world.resize(layout);
+                }
             }
 
             // Update internal state and request a redraw
-            world.update(&input);
+            let mouse = input
+                .mouse()
+                .and_then(|position| pixels.window_pos_to_pixel(position).ok());
+            world.update(&input, mouse, Instant::now());
             window.request_redraw();
         }
     });
 }
+
+/// the argument after a flag, like the 100 in `--simulate 100`
+fn arg_value(flag: &str) -> Option<String> {
+    env::args().skip_while(|arg| arg != flag).nth(1)
+}
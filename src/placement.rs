@@ -0,0 +1,240 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+
+use crate::game::{Cell, CELL_COUNT};
+
+/// ways for a computer player to lay out its fleet
+///
+/// apart from `Uniform`, each ship is picked at random from every place it fits around the
+/// ships before it, weighted by how much the placement likes the spot. that favors the spots
+/// left open by the ships placed first, but there's nothing to reroll
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Placement {
+    /// every legal layout of the whole fleet is as likely as any other
+    Uniform,
+    /// stays off the outside rows and columns, where it can
+    AvoidEdges,
+    /// keeps the ships well apart, and never touching
+    SpreadOut,
+    /// puts ships where there are the fewest ways to fit a ship, which is where an opponent
+    /// hunting by the odds of a ship being there looks last
+    AntiDensity,
+}
+
+impl Placement {
+    /// the cells of each ship, bow first, in the same order as the lengths. an error if they
+    /// can't all fit
+    pub(crate) fn place(
+        &self,
+        lengths: &[u8],
+        no_touching: bool,
+        rng: &mut StdRng,
+    ) -> Result<Vec<Vec<Cell>>, String> {
+        let mut fleet = Vec::new();
+        let density = density(lengths);
+        if !self.place_rest(lengths, no_touching, &density, rng, &mut fleet) {
+            return Err(format!(
+                "there's no room on the grid for ships {:?} long",
+                lengths
+            ));
+        }
+        if *self == Placement::Uniform {
+            // (the fleet just placed only made sure there is one, which `uniform` needs)
+            return Ok(uniform(lengths, no_touching, rng));
+        }
+        Ok(fleet)
+    }
+
+    /// place the rest of the ships one at a time, backing up to try another spot for the
+    /// ship before if one doesn't fit anywhere
+    fn place_rest(
+        &self,
+        lengths: &[u8],
+        no_touching: bool,
+        density: &Density,
+        rng: &mut StdRng,
+        fleet: &mut Vec<Vec<Cell>>,
+    ) -> bool {
+        let (len, rest) = match lengths.split_first() {
+            Some(split) => split,
+            None => return true,
+        };
+        let no_touching = no_touching || *self == Placement::SpreadOut;
+        // a weighted shuffle: each spot is keyed by a random number to the power of one over
+        // its weight, and tried from the biggest key down
+        let mut spots: Vec<(f64, Vec<Cell>)> = spots(*len)
+            .filter(|ship| fits(ship, fleet, no_touching))
+            .map(|ship| {
+                let key = rng
+                    .gen::<f64>()
+                    .powf(1.0 / self.weight(&ship, fleet, density));
+                (key, ship)
+            })
+            .collect();
+        spots.sort_by(|a, b| b.0.total_cmp(&a.0));
+        for (_, mut ship) in spots {
+            // either end can be the bow
+            if rng.gen() {
+                ship.reverse();
+            }
+            fleet.push(ship);
+            if self.place_rest(rest, no_touching, density, rng, fleet) {
+                return true;
+            }
+            fleet.pop();
+        }
+        false
+    }
+
+    /// how much more this placement likes a spot than others, always above 0
+    fn weight(&self, ship: &[Cell], fleet: &[Vec<Cell>], density: &Density) -> f64 {
+        match self {
+            Placement::Uniform => 1.0,
+            Placement::AvoidEdges => {
+                let last = CELL_COUNT as u8 - 1;
+                let on_edge = ship
+                    .iter()
+                    .filter(|cell| cell.x == 0 || cell.y == 0 || cell.x == last || cell.y == last)
+                    .count();
+                1.0 / (1.0 + 4.0 * on_edge as f64)
+            }
+            Placement::SpreadOut => {
+                // how many cells it is to the closest ship, counting diagonals as one
+                let distance = fleet
+                    .iter()
+                    .flatten()
+                    .flat_map(|other| {
+                        ship.iter().map(move |cell| {
+                            (cell.x as i8 - other.x as i8)
+                                .abs()
+                                .max((cell.y as i8 - other.y as i8).abs())
+                        })
+                    })
+                    .min()
+                    .unwrap_or(CELL_COUNT as i8);
+                (distance as f64).powi(3)
+            }
+            Placement::AntiDensity => {
+                let most = density.iter().flatten().cloned().max().unwrap_or(1) as f64;
+                let mean = ship
+                    .iter()
+                    .map(|cell| density[cell.y as usize][cell.x as usize] as f64)
+                    .sum::<f64>()
+                    / ship.len() as f64;
+                (most / mean).powi(4)
+            }
+        }
+    }
+}
+
+/// a fleet with each ship on any of the spots it fits on an empty grid, thrown down again
+/// until nothing overlaps. every throw is as likely as any other, so every legal layout is too
+///
+/// there's no limit on the throws, so there has to be a legal layout. the fleet the game uses
+/// lands on one about 2 throws in 5, or 1 in 20 with the no-touching rule
+fn uniform(lengths: &[u8], no_touching: bool, rng: &mut StdRng) -> Vec<Vec<Cell>> {
+    let spots: Vec<Vec<Vec<Cell>>> = lengths.iter().map(|len| spots(*len).collect()).collect();
+    loop {
+        let mut fleet: Vec<Vec<Cell>> = Vec::new();
+        for spots in spots.iter() {
+            let mut ship = match spots.choose(rng) {
+                Some(ship) if fits(ship, &fleet, no_touching) => ship.clone(),
+                _ => break,
+            };
+            // either end can be the bow
+            if rng.gen() {
+                ship.reverse();
+            }
+            fleet.push(ship);
+        }
+        if fleet.len() == lengths.len() {
+            return fleet;
+        }
+    }
+}
+
+/// every spot a ship of some length fits on an empty grid, across or down
+pub(crate) fn spots(len: u8) -> impl Iterator<Item = Vec<Cell>> {
+    let count = CELL_COUNT as u8;
+    (0..count).flat_map(move |y| {
+        (0..count).flat_map(move |x| {
+            let across =
+                Some((0..len).map(|i| Cell { x: x + i, y }).collect()).filter(|_| x + len <= count);
+            let down =
+                Some((0..len).map(|i| Cell { x, y: y + i }).collect()).filter(|_| y + len <= count);
+            across.into_iter().chain(down)
+        })
+    })
+}
+
+/// whether a ship can go here, around the rest of the fleet
+fn fits(ship: &[Cell], fleet: &[Vec<Cell>], no_touching: bool) -> bool {
+    fleet.iter().flatten().all(|other| {
+        ship.iter().all(|cell| {
+            if no_touching {
+                !cell.touches(other)
+            } else {
+                cell != other
+            }
+        })
+    })
+}
+
+/// for each cell, row by row, how many ways there are to fit a ship over it
+type Density = [[u32; CELL_COUNT]; CELL_COUNT];
+
+/// the density of an empty grid, counting every ship in the fleet, so lengths there are two
+/// of count twice
+fn density(lengths: &[u8]) -> Density {
+    let mut density = [[0; CELL_COUNT]; CELL_COUNT];
+    for len in lengths.iter() {
+        for ship in spots(*len) {
+            for cell in ship {
+                density[cell.y as usize][cell.x as usize] += 1;
+            }
+        }
+    }
+    density
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    const PLACEMENTS: [Placement; 4] = [
+        Placement::Uniform,
+        Placement::AvoidEdges,
+        Placement::SpreadOut,
+        Placement::AntiDensity,
+    ];
+
+    #[test]
+    fn every_placement_follows_the_rules() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let lengths = [5, 4, 3, 3, 2];
+        for placement in PLACEMENTS.iter() {
+            for no_touching in [false, true].iter() {
+                let fleet = placement.place(&lengths, *no_touching, &mut rng).unwrap();
+                let mut placed = Vec::new();
+                for (ship, len) in fleet.into_iter().zip(lengths.iter()) {
+                    let mut sorted = ship.clone();
+                    sorted.sort_by_key(|cell| (cell.y, cell.x));
+                    assert!(spots(*len).any(|spot| spot == sorted), "{:?}", ship);
+                    assert!(fits(&ship, &placed, *no_touching), "{:?}", placement);
+                    placed.push(ship);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn no_room_for_the_fleet() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for placement in PLACEMENTS.iter() {
+            // too long for the grid
+            assert!(placement.place(&[11], false, &mut rng).is_err());
+            // no two whole rows can be next to each other
+            assert!(placement.place(&[10; 6], true, &mut rng).is_err());
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::colors::Color;
+use crate::game::Cell;
+use crate::shapes::Shape;
+
+/// which part of the text its offset is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// a line of text, with its size and offset in design coordinates
+/// the offset is the top of the text, at its left edge, center or right edge
+#[derive(Debug, Clone, PartialEq)]
+pub struct Text {
+    pub text: String,
+    pub color: Color,
+    pub height: f32,
+    pub offset: (f32, f32),
+    pub align: Align,
+}
+
+/// everything drawn in one frame, kept around to work out what changed by the next one
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Scene {
+    /// board screens have two empty grids underneath everything else
+    pub grids: bool,
+    /// shapes drawn in cells on top of the grids, in drawing order. `true` for this player's grid
+    pub cells: Vec<(Cell, bool, Shape, Color)>,
+    pub texts: Vec<Text>,
+}
+
+impl Scene {
+    pub fn fill_cell(&mut self, cell: &Cell, shape: Shape, color: Color, this_player: bool) {
+        self.cells.push((cell.clone(), this_player, shape, color));
+    }
+
+    pub fn text(&mut self, text: &str, color: Color, height: f32, offset: (f32, f32)) {
+        self.aligned_text(text, color, height, offset, Align::Left);
+    }
+
+    pub fn aligned_text(
+        &mut self,
+        text: &str,
+        color: Color,
+        height: f32,
+        offset: (f32, f32),
+        align: Align,
+    ) {
+        self.texts.push(Text {
+            text: text.to_string(),
+            color,
+            height,
+            offset,
+            align,
+        });
+    }
+
+    /// the shapes drawn in one cell, bottom to top
+    pub fn fills<'a>(
+        &'a self,
+        cell: &'a Cell,
+        this_player: bool,
+    ) -> impl Iterator<Item = (Shape, Color)> + 'a {
+        self.cells
+            .iter()
+            .filter(move |(c, t, _, _)| c == cell && *t == this_player)
+            .map(|(_, _, shape, color)| (*shape, *color))
+    }
+
+    /// cells that look different in this scene than in the last one
+    pub fn changed_cells(&self, last: &Scene) -> Vec<(Cell, bool)> {
+        let mut changed: Vec<(Cell, bool)> = Vec::new();
+        for (cell, this_player, _, _) in self.cells.iter().chain(last.cells.iter()) {
+            let key = (cell.clone(), *this_player);
+            if !changed.contains(&key)
+                && !self
+                    .fills(cell, *this_player)
+                    .eq(last.fills(cell, *this_player))
+            {
+                changed.push(key);
+            }
+        }
+        changed
+    }
+
+    /// text in this scene that wasn't in the last one
+    pub fn new_texts<'a>(&'a self, last: &'a Scene) -> impl Iterator<Item = &'a Text> + 'a {
+        self.texts
+            .iter()
+            .filter(move |text| !last.texts.contains(text))
+    }
+}
@@ -0,0 +1,142 @@
+use crate::colors::{blend, Color};
+use crate::layout::{Rect, CELL_MARGIN, CELL_WIDTH};
+use crate::sprites::Part;
+
+/// the gap between a filled shape and the grid lines, as a fraction of the cell
+const MARGIN: f32 = CELL_MARGIN / CELL_WIDTH;
+
+/// something drawn in a grid cell, so hits, misses and targets differ by more than color
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    /// a hit
+    Cross,
+    /// a miss
+    Dot,
+    /// where someone is aiming: a ring with tick marks
+    Reticle,
+    /// one cell of a ship. the hull runs on into the neighbouring cells it joins,
+    /// and is rounded off at the bow and stern
+    Hull {
+        up: bool,
+        down: bool,
+        left: bool,
+        right: bool,
+    },
+    /// one cell of a ship, drawn from the ship sprites, turned clockwise by quarter turns
+    Sprite { part: Part, turns: u8 },
+    /// animation frames, for how far along the animation is, from 0 to 1
+    ///
+    /// rings spreading out from a miss
+    Ripple(f32),
+    /// a spiky fireball growing out of a hit
+    Burst(f32),
+    /// a flickering flame on a sinking ship
+    Flame(f32),
+}
+
+impl Shape {
+    /// how far a point is from the edge of the shape, negative inside it
+    ///
+    /// points go from (0, 0) to (1, 1) across the inside of the cell
+    fn distance(&self, point: (f32, f32)) -> f32 {
+        match *self {
+            Shape::Cross => {
+                let (low, high) = (0.22, 0.78);
+                let down = segment_distance(point, (low, low), (high, high));
+                let up = segment_distance(point, (low, high), (high, low));
+                down.min(up) - 0.07
+            }
+            Shape::Dot => length((point.0 - 0.5, point.1 - 0.5)) - 0.17,
+            Shape::Reticle => {
+                let ring = (length((point.0 - 0.5, point.1 - 0.5)) - 0.3).abs() - 0.04;
+                let ticks = [
+                    ((0.5, 0.08), (0.5, 0.3)),
+                    ((0.5, 0.7), (0.5, 0.92)),
+                    ((0.08, 0.5), (0.3, 0.5)),
+                    ((0.7, 0.5), (0.92, 0.5)),
+                ]
+                .iter()
+                .map(|&(a, b)| segment_distance(point, a, b) - 0.035)
+                .fold(f32::MAX, f32::min);
+                ring.min(ticks)
+            }
+            Shape::Hull {
+                up,
+                down,
+                left,
+                right,
+            } => {
+                // a rounded rectangle around a core that reaches past the cell on joined sides,
+                // and stops at the middle of the cell otherwise
+                let radius = 0.5 - MARGIN;
+                let reach = |joined: bool, past: f32| if joined { past } else { 0.5 };
+                let (x0, x1) = (reach(left, -1.0), reach(right, 2.0));
+                let (y0, y1) = (reach(up, -1.0), reach(down, 2.0));
+                let dx = (x0 - point.0).max(point.0 - x1).max(0.0);
+                let dy = (y0 - point.1).max(point.1 - y1).max(0.0);
+                length((dx, dy)) - radius
+            }
+            // drawn from bitmaps by `ShipSprites` instead, so there's nothing to fill in here
+            Shape::Sprite { .. } => f32::MAX,
+            Shape::Ripple(progress) => {
+                let distance = length((point.0 - 0.5, point.1 - 0.5));
+                let outer = 0.1 + 0.45 * progress;
+                let inner = outer - 0.2;
+                let ring = |radius: f32| (distance - radius).abs() - 0.03;
+                if inner > 0.0 {
+                    ring(outer).min(ring(inner))
+                } else {
+                    ring(outer)
+                }
+            }
+            Shape::Burst(progress) => {
+                let (x, y) = (point.0 - 0.5, point.1 - 0.5);
+                let spikes = 1.0 + 0.3 * (8.0 * y.atan2(x)).cos();
+                length((x, y)) - (0.15 + 0.3 * progress) * spikes
+            }
+            Shape::Flame(progress) => {
+                // a teardrop, from a round base up to a point that flickers up and down
+                let flicker = (progress * 40.0).sin() * 0.06;
+                let (base, tip) = ((0.5, 0.7), (0.5, 0.15 - flicker));
+                let along = ((base.1 - point.1) / (base.1 - tip.1)).clamp(0.0, 1.0);
+                let radius = 0.25 * (1.0 - along);
+                segment_distance(point, base, tip) - radius
+            }
+        }
+    }
+
+    /// draw the shape over a rectangle of the frame, blended with what's there already
+    pub fn paint(&self, frame: &mut [u8], frame_size: (usize, usize), rect: Rect, color: Color) {
+        let right = (rect.x + rect.width).min(frame_size.0);
+        let bottom = (rect.y + rect.height).min(frame_size.1);
+        let (width, height) = (rect.width as f32, rect.height as f32);
+        for y in rect.y..bottom {
+            for x in rect.x..right {
+                // the middle of the pixel
+                let point = (
+                    ((x - rect.x) as f32 + 0.5) / width,
+                    ((y - rect.y) as f32 + 0.5) / height,
+                );
+                // antialias over about a pixel at the edge
+                let coverage = (0.5 - self.distance(point) * width).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    let alpha = (color[3] as f32 * coverage) as u8;
+                    let i = (y * frame_size.0 + x) * 4;
+                    blend(&mut frame[i..i + 4], [color[0], color[1], color[2], alpha]);
+                }
+            }
+        }
+    }
+}
+
+fn length((x, y): (f32, f32)) -> f32 {
+    (x * x + y * y).sqrt()
+}
+
+/// distance from a point to the closest point on the line segment from a to b
+fn segment_distance(point: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (abx, aby) = (b.0 - a.0, b.1 - a.1);
+    let (apx, apy) = (point.0 - a.0, point.1 - a.1);
+    let t = ((apx * abx + apy * aby) / (abx * abx + aby * aby)).clamp(0.0, 1.0);
+    length((apx - t * abx, apy - t * aby))
+}
@@ -0,0 +1,109 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::game::{Rules, World};
+use crate::strategy::Strategy;
+
+/// play two computer players against each other some number of games, with no window, and
+/// print how each side did. the same seed plays out the same games
+pub(crate) fn run(games: u32, seed: u64, rules: &Rules, strategies: &mut [Box<dyn Strategy>; 2]) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    // the shots each side took in the games it won
+    let mut wins: [Vec<usize>; 2] = Default::default();
+    for game in 0..games {
+        // take turns going first, so neither side gets the edge
+        match World::play_computer_game(&mut rng, rules, strategies, game as usize % 2) {
+            Ok((winner, shots)) => wins[winner].push(shots),
+            Err(e) => return println!("{}", e),
+        }
+    }
+
+    let no_touching = if rules.no_touching {
+        ", no-touching rule"
+    } else {
+        ""
+    };
+    println!("{} games, seed {}{}", games, seed, no_touching);
+    println!(
+        "{:<20}{:>7}{:>18}{:>20}{:>9}",
+        "", "wins", "win rate", "mean shots to win", "median"
+    );
+    for (i, shots) in wins.iter_mut().enumerate() {
+        let rate = shots.len() as f64 / games.max(1) as f64;
+        // 95% confidence intervals, from the normal approximation
+        let rate_interval = 1.96 * (rate * (1.0 - rate) / games.max(1) as f64).sqrt();
+        let mean = mean(shots);
+        let mean_interval = 1.96 * standard_deviation(shots, mean) / (shots.len() as f64).sqrt();
+        let mean = match mean {
+            Some(mean) => format!("{:.1} ± {:.1}", mean, mean_interval),
+            None => "-".to_string(),
+        };
+        let median = median(shots).map_or("-".to_string(), |median| format!("{:.1}", median));
+        println!(
+            "{:<20}{:>7}{:>18}{:>20}{:>9}",
+            format!("{} ({})", strategies[i].name(), i + 1),
+            shots.len(),
+            format!("{:.1}% ± {:.1}%", rate * 100.0, rate_interval * 100.0),
+            mean,
+            median
+        );
+    }
+}
+
+fn mean(values: &[usize]) -> Option<f64> {
+    Some(values.iter().sum::<usize>() as f64 / values.len() as f64).filter(|_| !values.is_empty())
+}
+
+/// the sample standard deviation, or 0 without enough values to have one
+fn standard_deviation(values: &[usize], mean: Option<f64>) -> f64 {
+    match mean {
+        Some(mean) if values.len() > 1 => {
+            let squares: f64 = values.iter().map(|&v| (v as f64 - mean).powi(2)).sum();
+            (squares / (values.len() - 1) as f64).sqrt()
+        }
+        _ => 0.0,
+    }
+}
+
+fn median(values: &mut [usize]) -> Option<f64> {
+    values.sort_unstable();
+    let middle = values.len() / 2;
+    match values.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((values[middle - 1] + values[middle]) as f64 / 2.0),
+        _ => Some(values[middle] as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mean_of_odd_and_even_counts() {
+        assert_eq!(mean(&[3, 5, 10]), Some(6.0));
+        assert_eq!(mean(&[3, 5, 10, 2]), Some(5.0));
+        assert_eq!(mean(&[]), None);
+    }
+
+    #[test]
+    fn median_of_an_odd_count() {
+        assert_eq!(median(&mut [10, 3, 5]), Some(5.0));
+        assert_eq!(median(&mut [7]), Some(7.0));
+    }
+
+    #[test]
+    fn median_of_an_even_count() {
+        assert_eq!(median(&mut [10, 3, 5, 2]), Some(4.0));
+        assert_eq!(median(&mut [4, 1]), Some(2.5));
+        assert_eq!(median(&mut []), None);
+    }
+
+    #[test]
+    fn standard_deviation_needs_two_values() {
+        assert_eq!(
+            standard_deviation(&[2, 4, 4, 4, 5, 5, 7, 9], Some(5.0)),
+            (32.0f64 / 7.0).sqrt()
+        );
+        assert_eq!(standard_deviation(&[7], Some(7.0)), 0.0);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::colors::{blend, Color};
+use crate::layout::Rect;
+
+/// which part of a ship a cell is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Part {
+    Bow,
+    Mid,
+    Stern,
+}
+
+/// a decoded image, one color per pixel, row by row
+#[derive(Debug)]
+struct Sprite {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Sprite {
+    fn decode(name: &str, bytes: &[u8]) -> Result<Sprite, String> {
+        let error = |e: png::DecodingError| format!("{} is not a readable png, {}", name, e);
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let (info, mut reader) = decoder.read_info().map_err(error)?;
+        let mut buffer = vec![0; info.buffer_size()];
+        reader.next_frame(&mut buffer).map_err(error)?;
+
+        use png::ColorType::*;
+        let pixels = match info.color_type {
+            RGBA => buffer
+                .chunks_exact(4)
+                .map(|p| [p[0], p[1], p[2], p[3]])
+                .collect(),
+            RGB => buffer
+                .chunks_exact(3)
+                .map(|p| [p[0], p[1], p[2], 0xff])
+                .collect(),
+            GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            Grayscale => buffer.iter().map(|&v| [v, v, v, 0xff]).collect(),
+            Indexed => return Err(format!("{} has a palette that couldn't be expanded", name)),
+        };
+        Ok(Sprite {
+            width: info.width as usize,
+            height: info.height as usize,
+            pixels,
+        })
+    }
+}
+
+/// bitmaps for each part of a ship, drawn with the bow pointing up
+///
+/// they're shaded in grays and tinted with the theme's ship colors when drawn
+#[derive(Debug)]
+pub struct ShipSprites {
+    bow: Sprite,
+    mid: Sprite,
+    stern: Sprite,
+}
+
+impl ShipSprites {
+    /// decode the sprites built into the game, and check they fit together
+    pub fn load() -> Result<ShipSprites, String> {
+        let sprites = ShipSprites {
+            bow: Sprite::decode("bow.png", include_bytes!("../assets/ships/bow.png"))?,
+            mid: Sprite::decode("mid.png", include_bytes!("../assets/ships/mid.png"))?,
+            stern: Sprite::decode("stern.png", include_bytes!("../assets/ships/stern.png"))?,
+        };
+        let size = (sprites.bow.width, sprites.bow.height);
+        if size.0 != size.1 {
+            return Err(format!(
+                "bow.png should be square, but is {}x{}",
+                size.0, size.1
+            ));
+        }
+        for (name, sprite) in [("mid.png", &sprites.mid), ("stern.png", &sprites.stern)].iter() {
+            if (sprite.width, sprite.height) != size {
+                return Err(format!(
+                    "{} is {}x{}, but should be the same size as bow.png, {}x{}",
+                    name, sprite.width, sprite.height, size.0, size.1
+                ));
+            }
+        }
+        Ok(sprites)
+    }
+
+    /// draw part of a ship over a rectangle of the frame, turned clockwise by some
+    /// quarter turns, scaled to fit and tinted with a color
+    pub fn paint(
+        &self,
+        part: Part,
+        turns: u8,
+        frame: &mut [u8],
+        frame_size: (usize, usize),
+        rect: Rect,
+        tint: Color,
+    ) {
+        let sprite = match part {
+            Part::Bow => &self.bow,
+            Part::Mid => &self.mid,
+            Part::Stern => &self.stern,
+        };
+        let right = (rect.x + rect.width).min(frame_size.0);
+        let bottom = (rect.y + rect.height).min(frame_size.1);
+        for y in rect.y..bottom {
+            for x in rect.x..right {
+                // where this pixel is in the cell, then where that is in the unturned sprite
+                let u = ((x - rect.x) as f32 + 0.5) / rect.width as f32;
+                let v = ((y - rect.y) as f32 + 0.5) / rect.height as f32;
+                let (u, v) = match turns % 4 {
+                    0 => (u, v),
+                    1 => (v, 1.0 - u),
+                    2 => (1.0 - u, 1.0 - v),
+                    _ => (1.0 - v, u),
+                };
+                let sx = ((u * sprite.width as f32) as usize).min(sprite.width - 1);
+                let sy = ((v * sprite.height as f32) as usize).min(sprite.height - 1);
+                let pixel = sprite.pixels[sy * sprite.width + sx];
+                if pixel[3] == 0 {
+                    continue;
+                }
+                let shade = |i: usize| (pixel[i] as u32 * tint[i] as u32 / 255) as u8;
+                let alpha = (pixel[3] as u32 * tint[3] as u32 / 255) as u8;
+                let i = (y * frame_size.0 + x) * 4;
+                blend(&mut frame[i..i + 4], [shade(0), shade(1), shade(2), alpha]);
+            }
+        }
+    }
+}
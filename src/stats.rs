@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::files::{load_ron, save_ron};
+use crate::game::{Cell, GameType, CELL_COUNT};
+
+/// how games against one kind of opponent have gone
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Record {
+    pub(crate) played: u32,
+    pub(crate) won: u32,
+    pub(crate) lost: u32,
+    /// all the shots taken in games that were won, for the average
+    pub(crate) winning_shots: u32,
+    /// every shot taken, and how many of them hit
+    pub(crate) shots: u32,
+    pub(crate) hits: u32,
+    /// the fewest shots a game was won in
+    pub(crate) fastest_win: Option<u32>,
+    /// wins in a row, up to the last game played
+    pub(crate) streak: u32,
+    pub(crate) longest_streak: u32,
+}
+
+impl Record {
+    pub(crate) fn average_shots_to_win(&self) -> Option<f32> {
+        Some(self.winning_shots as f32 / self.won as f32).filter(|_| self.won > 0)
+    }
+
+    /// the share of shots that hit, from 0 to 1
+    pub(crate) fn accuracy(&self) -> Option<f32> {
+        Some(self.hits as f32 / self.shots as f32).filter(|_| self.shots > 0)
+    }
+}
+
+/// where the player has put their ships, over every fleet they've placed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Heatmap {
+    pub(crate) fleets: u32,
+    /// for each cell, row by row, how many of those fleets had a ship on it
+    pub(crate) cells: [[u32; CELL_COUNT]; CELL_COUNT],
+}
+
+/// finished games on this computer, kept across sessions as ron in the data directory
+///
+/// hotseat games are counted from player 1's side
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Stats {
+    pub(crate) records: BTreeMap<GameType, Record>,
+    /// the player's fleets, for the adaptive computer player to learn from
+    #[serde(default)]
+    pub(crate) fleets: Heatmap,
+}
+
+impl Stats {
+    fn path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_default()
+            .join("battleship")
+            .join("stats.ron")
+    }
+
+    /// read the stats from the data directory, starting from nothing if there aren't any yet
+    pub(crate) fn load() -> Result<Self, String> {
+        Ok(load_ron(&Stats::path())?.unwrap_or_default())
+    }
+
+    pub(crate) fn save(&self) -> Result<(), String> {
+        save_ron(&Stats::path(), self)
+    }
+
+    /// count a finished game, given the shots taken in it and how many of them hit
+    pub(crate) fn record(&mut self, game_type: GameType, won: bool, shots: u32, hits: u32) {
+        let record = self.records.entry(game_type).or_default();
+        record.played += 1;
+        record.shots += shots;
+        record.hits += hits;
+        if won {
+            record.won += 1;
+            record.winning_shots += shots;
+            record.fastest_win = Some(
+                record
+                    .fastest_win
+                    .map_or(shots, |fastest| fastest.min(shots)),
+            );
+            record.streak += 1;
+            record.longest_streak = record.longest_streak.max(record.streak);
+        } else {
+            record.lost += 1;
+            record.streak = 0;
+        }
+    }
+
+    /// count a fleet the player placed, from the cells of all its ships
+    pub(crate) fn record_fleet<'a>(&mut self, cells: impl Iterator<Item = &'a Cell>) {
+        self.fleets.fleets += 1;
+        for cell in cells {
+            self.fleets.cells[cell.y as usize][cell.x as usize] += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_a_win_and_a_loss() {
+        let mut stats = Stats::default();
+        stats.record(GameType::Ai, true, 50, 17);
+        stats.record(GameType::Ai, false, 70, 12);
+        let record = &stats.records[&GameType::Ai];
+        assert_eq!((record.played, record.won, record.lost), (2, 1, 1));
+        assert_eq!(
+            (record.shots, record.hits, record.winning_shots),
+            (120, 29, 50)
+        );
+        assert_eq!(record.average_shots_to_win(), Some(50.0));
+        assert_eq!(record.accuracy(), Some(29.0 / 120.0));
+    }
+
+    #[test]
+    fn record_streaks_and_the_fastest_win() {
+        let mut stats = Stats::default();
+        for (won, shots) in [(true, 60), (true, 40), (false, 80), (true, 45)] {
+            stats.record(GameType::Hotseat, won, shots, 17);
+        }
+        let record = &stats.records[&GameType::Hotseat];
+        assert_eq!(record.streak, 1);
+        assert_eq!(record.longest_streak, 2);
+        assert_eq!(record.fastest_win, Some(40));
+    }
+
+    #[test]
+    fn record_each_kind_of_game_apart() {
+        let mut stats = Stats::default();
+        stats.record(GameType::Hotseat, true, 50, 17);
+        stats.record(GameType::Ai, false, 60, 10);
+        stats.record(GameType::LocalNetwork, true, 40, 17);
+        stats.record(GameType::LocalNetwork, true, 55, 17);
+        assert_eq!(stats.records[&GameType::Hotseat].won, 1);
+        assert_eq!(stats.records[&GameType::Ai].lost, 1);
+        assert_eq!(stats.records[&GameType::Ai].fastest_win, None);
+        assert_eq!(stats.records[&GameType::LocalNetwork].played, 2);
+        assert_eq!(stats.records[&GameType::LocalNetwork].streak, 2);
+    }
+
+    #[test]
+    fn no_averages_without_games() {
+        let record = Record::default();
+        assert_eq!(record.average_shots_to_win(), None);
+        assert_eq!(record.accuracy(), None);
+    }
+}
@@ -0,0 +1,329 @@
+use rand::{rngs::StdRng, Rng};
+
+use crate::game::{Cell, CELL_COUNT};
+use crate::placement::{spots, Placement};
+use crate::stats::Heatmap;
+
+/// what a computer player can see of the fleet it's firing at
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Board {
+    /// where the target is now
+    pub(crate) target: Cell,
+    /// every shot taken so far, and whether it hit
+    pub(crate) shots: Vec<(Cell, bool)>,
+    /// the cells of each ship sunk so far
+    pub(crate) sunk: Vec<Vec<Cell>>,
+    /// the lengths of the ships still afloat
+    pub(crate) afloat: Vec<u8>,
+    /// ships can't touch, so nothing is next to a sunk ship
+    pub(crate) no_touching: bool,
+}
+
+impl Board {
+    pub(crate) fn fired_at(&self, cell: &Cell) -> bool {
+        self.shots.iter().any(|(shot, _)| shot == cell)
+    }
+
+    /// with the no-touching rule, there's no point shooting next to a ship that's already sunk
+    pub(crate) fn next_to_sunk_ship(&self, cell: &Cell) -> bool {
+        self.no_touching
+            && self
+                .sunk
+                .iter()
+                .any(|ship| ship.iter().any(|c| c.touches(cell)))
+    }
+}
+
+/// how a computer player plays: where it puts its fleet, and where it fires
+///
+/// `World` asks whichever one was picked in the settings, so a new computer player is
+/// just another implementation, added to `STRATEGIES`
+pub(crate) trait Strategy {
+    /// what it's called in the settings, and in `STRATEGIES`
+    fn name(&self) -> &'static str;
+
+    /// the cells of each ship, bow first, in the same order as the lengths. an error if they
+    /// can't all fit
+    fn place_fleet(
+        &mut self,
+        lengths: &[u8],
+        no_touching: bool,
+        rng: &mut StdRng,
+    ) -> Result<Vec<Vec<Cell>>, String>;
+
+    /// the cell to fire at next, which shouldn't have been fired at already
+    fn next_shot(&mut self, board: &Board, rng: &mut StdRng) -> Cell;
+
+    /// learn from where the player put their ships in games before this one
+    fn study(&mut self, _fleets: &Heatmap) {}
+}
+
+/// every computer player, by name, in the order they're offered in the settings
+///
+/// they're levels of difficulty, each both firing smarter and hiding its fleet better than the
+/// one before. adaptive is expert, learning where the player puts their ships as well
+pub(crate) const STRATEGIES: [&str; 5] = ["easy", "normal", "hard", "expert", "adaptive"];
+
+pub(crate) fn by_name(name: &str) -> Option<Box<dyn Strategy>> {
+    let strategy: Box<dyn Strategy> = match name {
+        "easy" => Box::new(RandomWalk {
+            placement: Placement::Uniform,
+        }),
+        "normal" => Box::new(HuntTarget {
+            name: "normal",
+            placement: Placement::AvoidEdges,
+            checkerboard: false,
+        }),
+        "hard" => Box::new(HuntTarget {
+            name: "hard",
+            placement: Placement::SpreadOut,
+            checkerboard: true,
+        }),
+        "expert" => Box::new(DensityHunter::new("expert", false)),
+        "adaptive" => Box::new(DensityHunter::new("adaptive", true)),
+        _ => return None,
+    };
+    Some(strategy)
+}
+
+/// the computer player to use when none has been picked
+pub(crate) fn default_strategy() -> Box<dyn Strategy> {
+    by_name(STRATEGIES[0]).unwrap()
+}
+
+/// walks the target around at random, and fires every so often
+pub(crate) struct RandomWalk {
+    placement: Placement,
+}
+
+impl Strategy for RandomWalk {
+    fn name(&self) -> &'static str {
+        "easy"
+    }
+
+    fn place_fleet(
+        &mut self,
+        lengths: &[u8],
+        no_touching: bool,
+        rng: &mut StdRng,
+    ) -> Result<Vec<Vec<Cell>>, String> {
+        self.placement.place(lengths, no_touching, rng)
+    }
+
+    fn next_shot(&mut self, board: &Board, rng: &mut StdRng) -> Cell {
+        let mut cell = board.target.clone();
+        loop {
+            // move or shoot with some %
+            // on average, move 10 times for every shot
+            let shoot: f64 = rng.gen();
+            if shoot <= 0.1 && !board.fired_at(&cell) && !board.next_to_sunk_ship(&cell) {
+                return cell;
+            }
+            let (x, y) = [(0, -1), (0, 1), (-1, 0), (1, 0)][rng.gen_range(0..4)];
+            let (x, y) = (cell.x as i8 + x, cell.y as i8 + y);
+            // moves off the grid go nowhere
+            if x >= 0 && x < CELL_COUNT as i8 && y >= 0 && y < CELL_COUNT as i8 {
+                cell = Cell {
+                    x: x as u8,
+                    y: y as u8,
+                };
+            }
+        }
+    }
+}
+
+/// fires at random until it hits something, then around the hit until the ship sinks
+pub(crate) struct HuntTarget {
+    name: &'static str,
+    placement: Placement,
+    /// hunt on every other cell only, like the black squares of a checkerboard. every ship is
+    /// at least two long, so it covers one of them
+    checkerboard: bool,
+}
+
+impl Strategy for HuntTarget {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn place_fleet(
+        &mut self,
+        lengths: &[u8],
+        no_touching: bool,
+        rng: &mut StdRng,
+    ) -> Result<Vec<Vec<Cell>>, String> {
+        self.placement.place(lengths, no_touching, rng)
+    }
+
+    fn next_shot(&mut self, board: &Board, rng: &mut StdRng) -> Cell {
+        let open = |cell: &Cell| !board.fired_at(cell) && !board.next_to_sunk_ship(cell);
+        let hit = |x: i8, y: i8| {
+            let on_grid = (0..CELL_COUNT as i8).contains(&x) && (0..CELL_COUNT as i8).contains(&y);
+            let cell = Cell {
+                x: x as u8,
+                y: y as u8,
+            };
+            on_grid
+                && board.shots.contains(&(cell.clone(), true))
+                && !board.sunk.iter().flatten().any(|sunk| *sunk == cell)
+        };
+
+        // the open cells next to a hit, and the ones that carry on a line of two hits
+        let mut around = Vec::new();
+        let mut in_line = Vec::new();
+        for (cell, _) in board
+            .shots
+            .iter()
+            .filter(|(c, _)| hit(c.x as i8, c.y as i8))
+        {
+            for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)].iter() {
+                let (x, y) = (cell.x as i8 + dx, cell.y as i8 + dy);
+                if x < 0 || x >= CELL_COUNT as i8 || y < 0 || y >= CELL_COUNT as i8 {
+                    continue;
+                }
+                let next = Cell {
+                    x: x as u8,
+                    y: y as u8,
+                };
+                if !open(&next) {
+                    continue;
+                }
+                if hit(cell.x as i8 - dx, cell.y as i8 - dy) {
+                    in_line.push(next.clone());
+                }
+                around.push(next);
+            }
+        }
+        for targets in [in_line, around].iter() {
+            if !targets.is_empty() {
+                return targets[rng.gen_range(0..targets.len())].clone();
+            }
+        }
+
+        let open: Vec<Cell> = (0..CELL_COUNT as u8)
+            .flat_map(|y| (0..CELL_COUNT as u8).map(move |x| Cell { x, y }))
+            .filter(open)
+            .collect();
+        let black: Vec<Cell> = open
+            .iter()
+            .filter(|cell| (cell.x + cell.y) % 2 == 0)
+            .cloned()
+            .collect();
+        // (with the no-touching rule, every black cell can be ruled out before the last ship
+        // is found)
+        let hunt = if self.checkerboard && !black.is_empty() {
+            &black
+        } else {
+            &open
+        };
+        hunt[rng.gen_range(0..hunt.len())].clone()
+    }
+}
+
+/// how many times likelier a spot is, for each hit it would explain
+const HIT_WEIGHT: f64 = 20.0;
+/// how many fleets it takes before the player's habits count for as much as the odds
+const FLEETS_TO_TRUST: f64 = 10.0;
+
+/// fires wherever the most ships could still fit, and can learn where the player likes to put
+/// their ships
+#[derive(Debug)]
+pub(crate) struct DensityHunter {
+    name: &'static str,
+    /// whether it studies the player's fleets
+    learns: bool,
+    /// for each cell, row by row, how much likelier the player is to have a ship there than
+    /// the odds say. 1 until it's studied some fleets
+    habits: Option<[[f64; CELL_COUNT]; CELL_COUNT]>,
+}
+
+impl DensityHunter {
+    fn new(name: &'static str, learns: bool) -> Self {
+        DensityHunter {
+            name,
+            learns,
+            habits: None,
+        }
+    }
+}
+
+impl Strategy for DensityHunter {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn place_fleet(
+        &mut self,
+        lengths: &[u8],
+        no_touching: bool,
+        rng: &mut StdRng,
+    ) -> Result<Vec<Vec<Cell>>, String> {
+        Placement::AntiDensity.place(lengths, no_touching, rng)
+    }
+
+    fn next_shot(&mut self, board: &Board, rng: &mut StdRng) -> Cell {
+        let sunk: Vec<&Cell> = board.sunk.iter().flatten().collect();
+        let blocked = |cell: &Cell| {
+            board.shots.contains(&(cell.clone(), false))
+                || sunk.contains(&cell)
+                || board.next_to_sunk_ship(cell)
+        };
+        let hits: Vec<&Cell> = board
+            .shots
+            .iter()
+            .filter(|(cell, hit)| *hit && !sunk.contains(&cell))
+            .map(|(cell, _)| cell)
+            .collect();
+
+        // every spot a ship still afloat could be in, likelier the more hits it explains
+        let mut density = [[0.0; CELL_COUNT]; CELL_COUNT];
+        for len in &board.afloat {
+            for ship in spots(*len).filter(|ship| !ship.iter().any(blocked)) {
+                let explains = ship.iter().filter(|cell| hits.contains(cell)).count();
+                let weight = HIT_WEIGHT.powi(explains as i32);
+                for cell in ship {
+                    density[cell.y as usize][cell.x as usize] += weight;
+                }
+            }
+        }
+        // the player's habits only help find a ship. once one's been hit, the hits say far more
+        // about where the rest of it is
+        if let Some(habits) = self.habits.as_ref().filter(|_| hits.is_empty()) {
+            for (row, habits) in density.iter_mut().zip(habits) {
+                for (density, habit) in row.iter_mut().zip(habits) {
+                    *density *= habit;
+                }
+            }
+        }
+
+        let open: Vec<Cell> = (0..CELL_COUNT as u8)
+            .flat_map(|y| (0..CELL_COUNT as u8).map(move |x| Cell { x, y }))
+            .filter(|cell| !board.fired_at(cell) && !board.next_to_sunk_ship(cell))
+            .collect();
+        let score = |cell: &Cell| density[cell.y as usize][cell.x as usize];
+        let best = open.iter().map(score).fold(0.0, f64::max);
+        // a tie goes to any of them, so it can't be read
+        let picks: Vec<&Cell> = open
+            .iter()
+            .filter(|cell| score(cell) >= best * 0.999)
+            .collect();
+        picks[rng.gen_range(0..picks.len())].clone()
+    }
+
+    fn study(&mut self, fleets: &Heatmap) {
+        if !self.learns || fleets.fleets == 0 {
+            return;
+        }
+        let total: u32 = fleets.cells.iter().flatten().sum();
+        let average = total as f64 / (CELL_COUNT * CELL_COUNT) as f64;
+        // lean on the habits more, the more fleets there are to go on
+        let trust = fleets.fleets as f64 / (fleets.fleets as f64 + FLEETS_TO_TRUST);
+        let mut habits = [[1.0; CELL_COUNT]; CELL_COUNT];
+        for (row, counts) in habits.iter_mut().zip(&fleets.cells) {
+            for (habit, count) in row.iter_mut().zip(counts) {
+                *habit = 1.0 - trust + trust * *count as f64 / average;
+            }
+        }
+        self.habits = Some(habits);
+    }
+}
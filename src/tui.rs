@@ -0,0 +1,355 @@
+//! the game in a terminal, drawn as text with ansi colors, for playing over ssh
+//!
+//! started with `--tui`, it runs the same `World` as the window does, just with keys read
+//! from the terminal and scenes drawn as text, so a game in the terminal can play one in a
+//! window over the network
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::{
+    Color as TermColor, Print, ResetColor, SetBackgroundColor, SetForegroundColor,
+};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::io::{self, Write};
+use std::panic;
+use std::time::{Duration, Instant};
+use winit::event::VirtualKeyCode;
+use winit_input_helper::TextChar;
+
+use crate::colors::{Color, Theme};
+use crate::game::{Cell, World, CELL_COUNT};
+use crate::keys::{key_for_char, Input};
+use crate::layout::{Layout, DESIGN_HEIGHT, DESIGN_WIDTH, TOP_MARGIN};
+use crate::scene::{Align, Scene};
+use crate::shapes::Shape;
+
+/// design pixels across one column of text
+const COLUMN_WIDTH: f32 = 10.0;
+/// design pixels down one row of text, away from the grids. across the grids, a row is a cell
+const ROW_HEIGHT: f32 = 20.0;
+/// how long to wait for a key before updating anyway, for the other player's moves and
+/// animations
+const TICK: Duration = Duration::from_millis(30);
+
+/// a key pressed in the terminal, as the window would have seen it
+#[derive(Default)]
+struct TerminalInput {
+    keys: Vec<VirtualKeyCode>,
+    text: Vec<TextChar>,
+    /// ctrl-c, which can't be bound to anything else
+    quit: bool,
+}
+
+impl TerminalInput {
+    fn from_key(key: KeyEvent) -> Self {
+        use VirtualKeyCode::*;
+        let mut input = TerminalInput::default();
+        let pressed = match key.code {
+            KeyCode::Up => Some(Up),
+            KeyCode::Down => Some(Down),
+            KeyCode::Left => Some(Left),
+            KeyCode::Right => Some(Right),
+            KeyCode::Enter => Some(Return),
+            KeyCode::Tab => Some(Tab),
+            KeyCode::Esc => Some(Escape),
+            KeyCode::F(5) => Some(F5),
+            KeyCode::Backspace => {
+                input.text.push(TextChar::Back);
+                Some(Back)
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                input.quit = true;
+                None
+            }
+            KeyCode::Char(' ') => Some(Space),
+            KeyCode::Char(c) => {
+                input.text.push(TextChar::Char(c));
+                key_for_char(c)
+            }
+            _ => None,
+        };
+        input.keys.extend(pressed);
+        input
+    }
+}
+
+impl Input for TerminalInput {
+    fn key_pressed(&self, key: VirtualKeyCode) -> bool {
+        self.keys.contains(&key)
+    }
+
+    fn text(&self) -> Vec<TextChar> {
+        self.text.clone()
+    }
+
+    // there's no mouse in the terminal
+    fn mouse_pressed(&self, _button: usize) -> bool {
+        false
+    }
+
+    fn mouse_released(&self, _button: usize) -> bool {
+        false
+    }
+
+    fn scroll_diff(&self) -> f32 {
+        0.0
+    }
+}
+
+/// play in the terminal until escape or ctrl-c, putting the terminal back how it was after
+pub fn run(world: &mut World) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    // a panic puts the terminal back before saying what went wrong, so it can be read
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = restore();
+        default_hook(info);
+    }));
+    terminal::enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen, Hide)?;
+    let result = play(world, &mut stdout);
+    restore()?;
+    // (after the terminal is back, so a reason it couldn't be saved stays on screen)
+    if let Err(e) = world.save_game() {
+        println!("{}", e);
+    }
+    result
+}
+
+/// connect a network game with the terminal back to normal, where ctrl-c works and what the
+/// connection prints can be read, then carry on
+fn connect(world: &mut World) -> io::Result<()> {
+    restore()?;
+    println!("waiting for the other player, ctrl-c to give up");
+    world.connect();
+    terminal::enable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        EnterAlternateScreen,
+        Hide,
+        Clear(ClearType::All)
+    )
+}
+
+/// the terminal as it was before the game started
+fn restore() -> io::Result<()> {
+    execute!(io::stdout(), ResetColor, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()
+}
+
+fn play(world: &mut World, stdout: &mut io::Stdout) -> io::Result<()> {
+    let mut last: Vec<String> = Vec::new();
+    loop {
+        // one update per key, so quick presses aren't lost
+        let input = match event::poll(TICK)? {
+            true => match event::read()? {
+                Event::Key(key) => TerminalInput::from_key(key),
+                Event::Resize(..) => {
+                    queue!(stdout, ResetColor, Clear(ClearType::All))?;
+                    last.clear();
+                    TerminalInput::default()
+                }
+                _ => TerminalInput::default(),
+            },
+            false => TerminalInput::default(),
+        };
+
+        // (escape cancels typing a cell first)
+        let escape = input.key_pressed(VirtualKeyCode::Escape) && !world.is_typing();
+        if escape || input.quit {
+            return Ok(());
+        }
+        if input.key_pressed(VirtualKeyCode::F5) {
+            if let Err(e) = world.save_game() {
+                world.notify(e);
+            }
+        }
+
+        if world.needs_connection() {
+            connect(world)?;
+            last.clear();
+        }
+        world.update(&input, None, Instant::now());
+        let lines = world.render_text();
+        if lines != last {
+            // only the lines that changed, to keep it quick over ssh
+            for (row, line) in lines.iter().enumerate() {
+                if last.get(row) != Some(line) {
+                    queue!(stdout, MoveTo(0, row as u16), Print(line))?;
+                }
+            }
+            // whatever's left of a longer screen before
+            queue!(
+                stdout,
+                MoveTo(0, lines.len() as u16),
+                ResetColor,
+                Clear(ClearType::FromCursorDown)
+            )?;
+            stdout.flush()?;
+            last = lines;
+        }
+    }
+}
+
+/// which row of text a design y coordinate is on
+///
+/// with the grids on screen, each row of cells gets a row of text, and everything above and
+/// below them is squeezed to fit
+struct Rows {
+    grids: bool,
+    cell_width: f32,
+}
+
+impl Rows {
+    fn row(&self, y: f32) -> usize {
+        if !self.grids {
+            return (y / ROW_HEIGHT) as usize;
+        }
+        let above = (TOP_MARGIN / ROW_HEIGHT).ceil();
+        let bottom = TOP_MARGIN + self.cell_width * CELL_COUNT as f32;
+        let row = if y < TOP_MARGIN {
+            (y / ROW_HEIGHT).min(above - 1.0)
+        } else if y < bottom {
+            above + (y - TOP_MARGIN) / self.cell_width
+        } else {
+            above + CELL_COUNT as f32 + (y - bottom) / ROW_HEIGHT
+        };
+        row as usize
+    }
+
+    fn count(&self) -> usize {
+        self.row(DESIGN_HEIGHT as f32 - 1.0) + 1
+    }
+}
+
+/// one character on screen, with its colors
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Glyph {
+    c: char,
+    fg: Color,
+    bg: Color,
+}
+
+/// a scene as lines of text, colored with ansi escapes
+pub(crate) fn draw(scene: &Scene, layout: &Layout, theme: &Theme) -> Vec<String> {
+    let columns = (DESIGN_WIDTH as f32 / COLUMN_WIDTH) as usize;
+    let rows = Rows {
+        grids: scene.grids,
+        cell_width: layout.cell_width(),
+    };
+    let blank = Glyph {
+        c: ' ',
+        fg: theme.text,
+        bg: theme.background,
+    };
+    let mut canvas = vec![vec![blank; columns]; rows.count()];
+
+    // the grids, with a dot in the middle of each empty cell in place of grid lines
+    if scene.grids {
+        for this_player in layout.grids() {
+            for y in 0..CELL_COUNT as u8 {
+                for x in 0..CELL_COUNT as u8 {
+                    let glyphs =
+                        cell_glyphs(&mut canvas, &rows, layout, &Cell { x, y }, this_player);
+                    let middle = glyphs.len() / 2;
+                    for (i, glyph) in glyphs.iter_mut().enumerate() {
+                        glyph.bg = theme.grid_empty;
+                        glyph.fg = theme.grid_lines;
+                        glyph.c = if i == middle { '·' } else { ' ' };
+                    }
+                }
+            }
+        }
+    }
+
+    for (cell, this_player, shape, color) in &scene.cells {
+        let glyphs = cell_glyphs(&mut canvas, &rows, layout, cell, *this_player);
+        let middle = glyphs.len() / 2;
+        let mark = match shape {
+            Shape::Hull { .. } | Shape::Sprite { .. } => {
+                for glyph in glyphs.iter_mut() {
+                    glyph.bg = *color;
+                    glyph.c = ' ';
+                }
+                continue;
+            }
+            Shape::Reticle => {
+                let last = glyphs.len() - 1;
+                glyphs[0] = Glyph {
+                    c: '[',
+                    fg: *color,
+                    ..glyphs[0]
+                };
+                glyphs[last] = Glyph {
+                    c: ']',
+                    fg: *color,
+                    ..glyphs[last]
+                };
+                continue;
+            }
+            Shape::Cross => 'X',
+            Shape::Dot => '•',
+            Shape::Ripple(_) => 'o',
+            Shape::Burst(_) => '*',
+            Shape::Flame(_) => '^',
+        };
+        glyphs[middle].c = mark;
+        glyphs[middle].fg = *color;
+    }
+
+    for text in &scene.texts {
+        let row = rows
+            .row(text.offset.1 + text.height / 2.0)
+            .min(canvas.len() - 1);
+        let len = text.text.chars().count() as f32;
+        let x = text.offset.0 / COLUMN_WIDTH;
+        let left = match text.align {
+            Align::Left => x,
+            Align::Center => x - len / 2.0,
+            Align::Right => x - len,
+        };
+        let left = left.round().max(0.0) as usize;
+        for (glyph, c) in canvas[row].iter_mut().skip(left).zip(text.text.chars()) {
+            glyph.c = c;
+            glyph.fg = text.color;
+        }
+    }
+
+    canvas.iter().map(|row| line(row)).collect()
+}
+
+/// the characters a grid cell covers, all on one row
+fn cell_glyphs<'a>(
+    canvas: &'a mut [Vec<Glyph>],
+    rows: &Rows,
+    layout: &Layout,
+    cell: &Cell,
+    this_player: bool,
+) -> &'a mut [Glyph] {
+    let width = layout.cell_width();
+    let grid_x = layout.grid_x(this_player);
+    let column = |x: u8| ((grid_x + width * x as f32) / COLUMN_WIDTH).round() as usize;
+    let row = rows.row(TOP_MARGIN + width * (cell.y as f32 + 0.5));
+    &mut canvas[row][column(cell.x)..column(cell.x + 1)]
+}
+
+/// a row of characters, with the colors set wherever they change
+fn line(glyphs: &[Glyph]) -> String {
+    let rgb = |color: Color| TermColor::Rgb {
+        r: color[0],
+        g: color[1],
+        b: color[2],
+    };
+    let mut line = String::new();
+    let mut colors = None;
+    for glyph in glyphs {
+        if colors != Some((glyph.fg, glyph.bg)) {
+            line += &SetForegroundColor(rgb(glyph.fg)).to_string();
+            line += &SetBackgroundColor(rgb(glyph.bg)).to_string();
+            colors = Some((glyph.fg, glyph.bg));
+        }
+        line.push(glyph.c);
+    }
+    line += &ResetColor.to_string();
+    line
+}